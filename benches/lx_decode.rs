@@ -0,0 +1,127 @@
+//! Compares the event-based LX decoder with a DOM-based decoder built on
+//! `minidom`, which is how `lx::decode_file` used to work.
+//!
+//! Both decoders are run on a generated file with the size of a full
+//! FlarmNet database. Besides the decoding time, the peak heap usage while
+//! decoding is measured, including the decoded records.
+//!
+//! Run with `cargo bench --bench lx_decode`.
+
+use flarmnet::{File, FlarmId, Record};
+use minidom::Element;
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::hint::black_box;
+use std::io::BufReader;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::Instant;
+
+const RECORDS: usize = 20_000;
+const ITERATIONS: u32 = 10;
+
+/// Allocator that keeps track of the current and the peak heap usage.
+struct PeakAllocator;
+
+static CURRENT: AtomicUsize = AtomicUsize::new(0);
+static PEAK: AtomicUsize = AtomicUsize::new(0);
+
+unsafe impl GlobalAlloc for PeakAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        let ptr = unsafe { System.alloc(layout) };
+        if !ptr.is_null() {
+            let current = CURRENT.fetch_add(layout.size(), Ordering::Relaxed) + layout.size();
+            PEAK.fetch_max(current, Ordering::Relaxed);
+        }
+        ptr
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        unsafe { System.dealloc(ptr, layout) };
+        CURRENT.fetch_sub(layout.size(), Ordering::Relaxed);
+    }
+}
+
+#[global_allocator]
+static ALLOCATOR: PeakAllocator = PeakAllocator;
+
+fn main() {
+    let data = flarmnet::lx::encode_file(&make_file()).unwrap();
+    println!("LX file with {RECORDS} records, {} KiB", data.len() / 1024);
+
+    bench("event-based (lx::decode_file)", &data, |data| {
+        flarmnet::lx::decode_file(data).unwrap().records.len()
+    });
+    bench("DOM-based (minidom)", &data, decode_dom);
+}
+
+fn bench(name: &str, data: &[u8], decode: impl Fn(&[u8]) -> usize) {
+    let baseline = CURRENT.load(Ordering::Relaxed);
+    PEAK.store(baseline, Ordering::Relaxed);
+    assert_eq!(decode(data), RECORDS);
+    let peak = PEAK.load(Ordering::Relaxed) - baseline;
+
+    let start = Instant::now();
+    for _ in 0..ITERATIONS {
+        black_box(decode(black_box(data)));
+    }
+    let time = start.elapsed() / ITERATIONS;
+
+    println!(
+        "{name:<30} {:>8.1} ms {:>8} KiB peak heap usage",
+        time.as_secs_f64() * 1000.,
+        peak / 1024
+    );
+}
+
+fn make_file() -> File {
+    let records = (0..RECORDS)
+        .map(|i| Record {
+            flarm_id: FlarmId::new(i as u32).unwrap(),
+            pilot_name: format!("Pilot {i}"),
+            airfield: "EDKA".to_string(),
+            plane_type: "LS6a".to_string(),
+            registration: format!("D-{:04}", i % 10_000),
+            call_sign: format!("{:02}", i % 100),
+            frequency: Some("123.500".parse().unwrap()),
+            extensions: Default::default(),
+        })
+        .collect();
+
+    File {
+        version: 28592,
+        records,
+        extensions: Default::default(),
+    }
+}
+
+/// Decodes the file by building a DOM of the decrypted XML first.
+fn decode_dom(data: &[u8]) -> usize {
+    let reader = BufReader::new(flarmnet::lx::cipher::Reader::new(data));
+    let mut reader = minidom::quick_xml::Reader::from_reader(reader);
+    let root = Element::from_reader(&mut reader).unwrap();
+
+    let records: Vec<_> = root
+        .children()
+        .filter(|child| child.name() == "FLARMDATA")
+        .map(convert)
+        .collect();
+
+    records.len()
+}
+
+fn convert(element: &Element) -> Record {
+    let text = |name| {
+        let child = element.children().find(|child| child.name() == name);
+        child.map(|child| child.text().trim().to_string())
+    };
+
+    Record {
+        flarm_id: element.attr("FlarmID").unwrap().parse().unwrap(),
+        pilot_name: text("NAME").unwrap_or_default(),
+        airfield: text("AIRFIELD").unwrap_or_default(),
+        plane_type: text("TYPE").unwrap_or_default(),
+        registration: text("REG").unwrap_or_default(),
+        call_sign: text("COMPID").unwrap_or_default(),
+        frequency: text("FREQUENCY").and_then(|it| it.parse().ok()),
+        extensions: Default::default(),
+    }
+}
@@ -5,7 +5,7 @@ use std::path::PathBuf;
 
 #[derive(Debug, Parser)]
 struct Options {
-    /// Path to the XCSoar format FLN file
+    /// Path to the FlarmNet file in any supported format
     input: PathBuf,
 
     /// Path to which the LX format FLN file will be written
@@ -17,17 +17,8 @@ fn main() -> anyhow::Result<()> {
     let options = Options::parse();
 
     let input_path = &options.input;
-    let content = std::fs::read_to_string(input_path)?;
-    let decoded = flarmnet::xcsoar::decode_file(&content)?;
-
-    let file = flarmnet::File {
-        version: decoded.version,
-        records: decoded
-            .records
-            .into_iter()
-            .filter_map(|it| it.ok())
-            .collect(),
-    };
+    let content = std::fs::read(input_path)?;
+    let file = flarmnet::decode_any(&content)?.into_file();
 
     let new_path = options
         .output
@@ -0,0 +1,217 @@
+use anyhow::{Context, bail};
+use clap::{Parser, Subcommand};
+use flarmnet::{EncodeReport, Extensions, File, Format, Record, diff};
+use std::error::Error;
+use std::path::PathBuf;
+
+#[derive(Debug, Parser)]
+#[command(version, about)]
+struct Options {
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Debug, Subcommand)]
+enum Command {
+    /// Converts a FlarmNet file into another format
+    Convert(ConvertOptions),
+}
+
+#[derive(Debug, clap::Args)]
+struct ConvertOptions {
+    /// Path to the input file
+    input: PathBuf,
+
+    /// Path to which the converted file will be written
+    output: PathBuf,
+
+    /// Format of the input file, detected automatically for FlarmNet files
+    #[arg(long, value_parser = parse_format)]
+    from: Option<FileFormat>,
+
+    /// Format of the converted file
+    #[arg(long, value_parser = parse_format)]
+    to: FileFormat,
+}
+
+/// The formats that the `convert` subcommand can read and write.
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum FileFormat {
+    FlarmNet(Format),
+    Csv,
+    Ogn,
+    OgnJson,
+}
+
+impl FileFormat {
+    const ALL: [FileFormat; 6] = [
+        FileFormat::FlarmNet(Format::Lx),
+        FileFormat::FlarmNet(Format::Tdb),
+        FileFormat::FlarmNet(Format::XCSoar),
+        FileFormat::Csv,
+        FileFormat::Ogn,
+        FileFormat::OgnJson,
+    ];
+
+    fn name(self) -> &'static str {
+        match self {
+            FileFormat::FlarmNet(Format::Lx) => "lx",
+            FileFormat::FlarmNet(Format::Tdb) => "tdb",
+            FileFormat::FlarmNet(Format::XCSoar) => "xcsoar",
+            FileFormat::Csv => "csv",
+            FileFormat::Ogn => "ogn",
+            FileFormat::OgnJson => "ogn-json",
+        }
+    }
+
+    fn decode(self, data: &[u8]) -> anyhow::Result<Decoded> {
+        let decoded = match self {
+            FileFormat::FlarmNet(format) => {
+                let decoded = format.decode(data)?;
+                // the format-specific error contains the location of the record
+                let records = decoded.records.into_iter().map(|result| {
+                    result.map_err(|error| error_chain(error.source().unwrap_or(&error)))
+                });
+                Decoded {
+                    version: decoded.version,
+                    records: records.collect(),
+                    extensions: decoded.extensions,
+                }
+            }
+            FileFormat::Csv => Decoded::from_records(flarmnet::csv::decode_file(data)?.records),
+            FileFormat::Ogn => Decoded::from_records(flarmnet::ogn::decode_reader(data)?.records),
+            FileFormat::OgnJson => {
+                Decoded::from_records(flarmnet::ogn::json::decode_reader(data)?.records)
+            }
+        };
+        Ok(decoded)
+    }
+
+    fn encode_with_report(self, file: &File) -> anyhow::Result<(Vec<u8>, EncodeReport)> {
+        let encoded = match self {
+            FileFormat::FlarmNet(format) => return Ok(format.encode_with_report(file)?),
+            FileFormat::Csv => flarmnet::csv::encode_file(file)?,
+            FileFormat::Ogn => flarmnet::ogn::encode_file(file)?,
+            FileFormat::OgnJson => flarmnet::ogn::json::encode_file(file)?,
+        };
+        Ok((encoded, EncodeReport::default()))
+    }
+}
+
+/// A decoded input file with the error messages of the invalid records.
+struct Decoded {
+    version: u32,
+    records: Vec<Result<Record, String>>,
+    extensions: Extensions,
+}
+
+impl Decoded {
+    /// Creates a decoded file without version and extensions, which the CSV
+    /// and OGN formats don't have.
+    fn from_records<E: Error>(records: Vec<Result<Record, E>>) -> Self {
+        let records = records
+            .into_iter()
+            .map(|result| result.map_err(|error| error_chain(&error)));
+        Decoded {
+            version: 0,
+            records: records.collect(),
+            extensions: Extensions::new(),
+        }
+    }
+
+    fn into_file(self) -> File {
+        File {
+            version: self.version,
+            records: self.records.into_iter().filter_map(Result::ok).collect(),
+            extensions: self.extensions,
+        }
+    }
+}
+
+fn main() -> anyhow::Result<()> {
+    let options = Options::parse();
+
+    match options.command {
+        Command::Convert(options) => convert(options),
+    }
+}
+
+fn convert(options: ConvertOptions) -> anyhow::Result<()> {
+    let content = std::fs::read(&options.input).context("failed to read input file")?;
+    let from = match options.from {
+        Some(format) => format,
+        None => Format::detect(&content)
+            .map(FileFormat::FlarmNet)
+            .context("unknown input file format, use --from to specify it")?,
+    };
+    let decoded = from
+        .decode(&content)
+        .context("failed to decode input file")?;
+    eprintln!(
+        "Read {} file, version {}, with {} records",
+        from.name(),
+        decoded.version,
+        decoded.records.len()
+    );
+
+    for (index, result) in decoded.records.iter().enumerate() {
+        if let Err(error) = result {
+            eprintln!("Skipping invalid record #{}: {}", index + 1, error);
+        }
+    }
+
+    let file = decoded.into_file();
+    let (encoded, report) = options
+        .to
+        .encode_with_report(&file)
+        .context("failed to encode file")?;
+
+    for field in &report.truncated {
+        eprintln!(
+            "Truncated {} of record {}: {:?} -> {:?}",
+            field.field, field.flarm_id, field.original, field.written
+        );
+    }
+
+    // decode the output again to find out whether records got lost
+    let converted = options
+        .to
+        .decode(&encoded)
+        .context("failed to decode converted file")?
+        .into_file();
+
+    let diff = diff(&file, &converted);
+    if !diff.added.is_empty() {
+        bail!("converted file contains unexpected records");
+    }
+    for record in &diff.removed {
+        eprintln!("Dropped record {}", record.flarm_id);
+    }
+
+    std::fs::write(&options.output, encoded).context("failed to write output file")?;
+    eprintln!(
+        "Wrote {} file with {} records",
+        options.to.name(),
+        converted.records.len()
+    );
+
+    Ok(())
+}
+
+/// Joins the messages of the error and all of its sources.
+fn error_chain(error: &dyn Error) -> String {
+    let causes: Vec<_> = std::iter::successors(Some(error), |&it| it.source())
+        .map(ToString::to_string)
+        .collect();
+    causes.join(": ")
+}
+
+fn parse_format(value: &str) -> Result<FileFormat, String> {
+    FileFormat::ALL
+        .into_iter()
+        .find(|format| format.name().eq_ignore_ascii_case(value))
+        .ok_or_else(|| {
+            let names: Vec<_> = FileFormat::ALL.iter().map(|it| it.name()).collect();
+            format!("expected one of: {}", names.join(", "))
+        })
+}
@@ -0,0 +1,111 @@
+use crate::{DecodeOptions, EncodeReport, Error, Extensions, File, Format, Record};
+
+#[derive(Debug)]
+pub struct DecodedFile {
+    pub format: Format,
+    pub version: u32,
+    pub records: Vec<Result<Record, Error>>,
+    pub extensions: Extensions,
+}
+
+impl DecodedFile {
+    #[cfg(any(feature = "lx", feature = "tdb", feature = "xcsoar"))]
+    pub(crate) fn new<E: Into<Error>>(
+        format: Format,
+        version: u32,
+        records: Vec<Result<Record, E>>,
+        extensions: Extensions,
+    ) -> Self {
+        let records = records
+            .into_iter()
+            .map(|result| result.map_err(Into::into))
+            .collect();
+
+        Self {
+            format,
+            version,
+            records,
+            extensions,
+        }
+    }
+
+    /// Converts the decoded file into a [File], skipping all records that
+    /// could not be decoded.
+    pub fn into_file(self) -> File {
+        File {
+            version: self.version,
+            records: self.records.into_iter().filter_map(Result::ok).collect(),
+            extensions: self.extensions,
+        }
+    }
+}
+
+/// Common interface of the format modules.
+///
+/// This trait is implemented by [crate::lx::Lx], [crate::tdb::Tdb] and
+/// [crate::xcsoar::XCSoar]. Use [Format] to select one of them at runtime.
+pub trait Codec {
+    /// The format that is handled by this codec.
+    const FORMAT: Format;
+
+    /// Decodes a FlarmNet file in this format.
+    fn decode(data: &[u8]) -> Result<DecodedFile, Error> {
+        Self::decode_with_options(data, &DecodeOptions::default())
+    }
+
+    /// Decodes a FlarmNet file in this format with the given
+    /// [DecodeOptions].
+    fn decode_with_options(data: &[u8], options: &DecodeOptions) -> Result<DecodedFile, Error>;
+
+    /// Encodes a FlarmNet file in this format.
+    fn encode(file: &File) -> Result<Vec<u8>, Error>;
+
+    /// Encodes a FlarmNet file in this format and reports the lossy changes
+    /// that have been made, e.g. truncated fields.
+    fn encode_with_report(file: &File) -> Result<(Vec<u8>, EncodeReport), Error>;
+}
+
+/// Decodes a FlarmNet file in any of the supported formats.
+///
+/// The format is detected from the file content via [Format::detect].
+///
+/// # Examples
+///
+#[cfg_attr(feature = "xcsoar", doc = "```")]
+#[cfg_attr(not(feature = "xcsoar"), doc = "```ignore")]
+/// # use flarmnet::Format;
+/// let file = br#"006fb0
+/// 3030303030304dfc6c6c6572202020202020202020202020202020442d3231383820202020202020202020202020202041534b2d3133202020202020202020202020202020442d32313838202020203132332e313530
+/// "#;
+///
+/// let result = flarmnet::decode_any(file).unwrap();
+/// assert_eq!(result.format, Format::XCSoar);
+/// assert_eq!(result.version, 28592);
+/// assert_eq!(result.records.len(), 1);
+/// ```
+pub fn decode_any(data: &[u8]) -> Result<DecodedFile, Error> {
+    decode_any_with_options(data, &DecodeOptions::default())
+}
+
+/// Decodes a FlarmNet file in any of the supported formats with the given
+/// [DecodeOptions], see [decode_any].
+pub fn decode_any_with_options(data: &[u8], options: &DecodeOptions) -> Result<DecodedFile, Error> {
+    let format = Format::detect(data).ok_or(Error::UnknownFormat)?;
+    format.decode_with_options(data, options)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::decode_any;
+    use insta::assert_debug_snapshot;
+
+    #[test]
+    fn decoding_fails_for_empty_file() {
+        assert_debug_snapshot!(decode_any(b"").unwrap_err(), @"UnknownFormat");
+    }
+
+    #[test]
+    fn decoding_fails_for_unknown_format() {
+        assert_debug_snapshot!(decode_any(b"foo\nbar\n").unwrap_err(), @"UnknownFormat");
+    }
+}
@@ -0,0 +1,267 @@
+use super::{Column, CsvOptions, FREQUENCY_KEY};
+use crate::{Extensions, Field, Frequency, ParseFrequencyError, Record};
+use std::io::Read;
+use thiserror::Error;
+
+#[derive(Error, Debug)]
+pub enum DecodeError {
+    #[error(transparent)]
+    Io(#[from] std::io::Error),
+    #[error(transparent)]
+    Csv(#[from] csv::Error),
+    #[error("missing column: {0}")]
+    MissingColumn(&'static str),
+    #[error("duplicate column: {0}")]
+    DuplicateColumn(Column),
+    #[error("invalid FLARM id: {0}")]
+    InvalidFlarmId(String),
+    #[error(transparent)]
+    InvalidFrequency(#[from] ParseFrequencyError),
+}
+
+#[derive(Debug)]
+pub struct DecodedFile {
+    pub records: Vec<Result<Record, DecodeError>>,
+}
+
+/// Decodes a CSV file with the default [CsvOptions].
+///
+/// # Examples
+///
+/// ```
+/// let file = b"flarm_id,registration,call_sign,frequency\n3EE3C7,D-0816,SG,130.53\n";
+///
+/// let result = flarmnet::csv::decode_file(file).unwrap();
+/// let record = result.records[0].as_ref().unwrap();
+/// assert_eq!(record.registration, "D-0816");
+/// assert_eq!(record.frequency.unwrap().to_string(), "130.530");
+/// ```
+pub fn decode_file(file: &[u8]) -> Result<DecodedFile, DecodeError> {
+    decode_file_with_options(file, &CsvOptions::default())
+}
+
+/// Decodes a CSV file, see [module documentation](super).
+pub fn decode_file_with_options(
+    file: &[u8],
+    options: &CsvOptions,
+) -> Result<DecodedFile, DecodeError> {
+    let (file, _, _) = options.encoding.decode(file);
+
+    let mut reader = csv::ReaderBuilder::new()
+        .delimiter(options.delimiter)
+        .flexible(true)
+        .trim(csv::Trim::All)
+        .from_reader(file.as_bytes());
+
+    let mut columns = Vec::new();
+    for name in reader.headers()? {
+        let column = options.column(name);
+        if let Some(column) = column
+            && columns.contains(&Some(column))
+        {
+            return Err(DecodeError::DuplicateColumn(column));
+        }
+        columns.push(column);
+    }
+
+    if !columns.contains(&Some(Column::FlarmId)) {
+        return Err(DecodeError::MissingColumn(Column::FlarmId.name()));
+    }
+
+    let records = reader
+        .records()
+        .filter(|row| !row.as_ref().is_ok_and(|row| row.iter().all(str::is_empty)))
+        .map(|row| decode_record(&columns, &row?, options))
+        .collect();
+
+    Ok(DecodedFile { records })
+}
+
+/// Decodes a CSV file from any [Read] implementation with the default
+/// [CsvOptions].
+///
+/// The whole file is read into memory first, since the character encoding
+/// has to be converted.
+pub fn decode_reader<R: Read>(reader: R) -> Result<DecodedFile, DecodeError> {
+    decode_reader_with_options(reader, &CsvOptions::default())
+}
+
+/// Decodes a CSV file from any [Read] implementation, see
+/// [decode_file_with_options] and [decode_reader].
+pub fn decode_reader_with_options<R: Read>(
+    mut reader: R,
+    options: &CsvOptions,
+) -> Result<DecodedFile, DecodeError> {
+    let mut file = Vec::new();
+    reader.read_to_end(&mut file)?;
+    decode_file_with_options(&file, options)
+}
+
+fn decode_record(
+    columns: &[Option<Column>],
+    row: &csv::StringRecord,
+    options: &CsvOptions,
+) -> Result<Record, DecodeError> {
+    let value = |column: Column| {
+        columns
+            .iter()
+            .position(|it| *it == Some(column))
+            .and_then(|index| row.get(index))
+            .unwrap_or_default()
+    };
+    let field = |field: Field| value(Column::Field(field)).to_string();
+
+    let flarm_id = value(Column::FlarmId);
+    let flarm_id = flarm_id
+        .parse()
+        .map_err(|_| DecodeError::InvalidFlarmId(flarm_id.to_string()))?;
+
+    let frequency = value(Column::Field(Field::Frequency));
+    let mut extensions = Extensions::new();
+    let frequency = match frequency.parse::<Frequency>() {
+        _ if frequency.is_empty() => None,
+        Ok(frequency) => Some(frequency),
+        Err(_) if options.accept_invalid_frequencies => {
+            extensions.insert(FREQUENCY_KEY.to_string(), frequency.to_string());
+            None
+        }
+        Err(error) => return Err(error.into()),
+    };
+
+    Ok(Record {
+        flarm_id,
+        pilot_name: field(Field::PilotName),
+        airfield: field(Field::Airfield),
+        plane_type: field(Field::PlaneType),
+        registration: field(Field::Registration),
+        call_sign: field(Field::CallSign),
+        frequency,
+        extensions,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{decode_file, decode_file_with_options, decode_reader_with_options};
+    use crate::Field;
+    use crate::csv::{Column, CsvOptions};
+    use insta::assert_debug_snapshot;
+
+    #[test]
+    fn decoding_skips_empty_rows_and_unknown_columns() {
+        let file = b"Flarm_ID, Notes ,Registration\n\
+                     3ee3c7,Tobias,D-0816\n\
+                     ,,\n";
+        let result = decode_file(file).unwrap();
+        assert_debug_snapshot!(result.records, @r###"
+        [
+            Ok(
+                Record {
+                    flarm_id: FlarmId(
+                        3EE3C7,
+                    ),
+                    pilot_name: "",
+                    airfield: "",
+                    plane_type: "",
+                    registration: "D-0816",
+                    call_sign: "",
+                    frequency: None,
+                    extensions: {},
+                },
+            ),
+        ]
+        "###);
+    }
+
+    #[test]
+    fn decoding_reports_invalid_rows() {
+        let file = b"flarm_id,frequency\nXYZ,\nDD1234,123.4567\n";
+        let options = CsvOptions {
+            accept_invalid_frequencies: false,
+            ..Default::default()
+        };
+        let result = decode_file_with_options(file, &options).unwrap();
+        assert_debug_snapshot!(result.records, @r###"
+        [
+            Err(
+                InvalidFlarmId(
+                    "XYZ",
+                ),
+            ),
+            Err(
+                InvalidFrequency(
+                    Invalid(
+                        "123.4567",
+                    ),
+                ),
+            ),
+        ]
+        "###);
+    }
+
+    #[test]
+    fn decoding_keeps_records_with_invalid_frequency() {
+        let file = b"flarm_id,registration,frequency\nDD1234,D-0816,129.970\n";
+        let result = decode_file(file).unwrap();
+        let record = result.records[0].as_ref().unwrap();
+        assert_eq!(record.registration, "D-0816");
+        assert_eq!(record.frequency, None);
+        assert_eq!(record.extensions["csv.frequency"], "129.970");
+    }
+
+    #[test]
+    fn decoding_fails_for_missing_or_duplicate_columns() {
+        assert_debug_snapshot!(decode_file(b"registration\nD-0816\n").unwrap_err(), @r###"
+        MissingColumn(
+            "flarm_id",
+        )
+        "###);
+
+        let options = CsvOptions {
+            columns: [(
+                "Kennzeichen".to_string(),
+                Column::Field(Field::Registration),
+            )]
+            .into(),
+            ..Default::default()
+        };
+        let file = b"flarm_id,registration,kennzeichen\n";
+        assert_debug_snapshot!(decode_file_with_options(file, &options).unwrap_err(), @r###"
+        DuplicateColumn(
+            Field(
+                Registration,
+            ),
+        )
+        "###);
+    }
+
+    #[test]
+    fn decoding_uses_delimiter_and_encoding() {
+        let options = CsvOptions {
+            delimiter: b';',
+            encoding: encoding_rs::WINDOWS_1252,
+            ..Default::default()
+        };
+        let file = b"flarm_id;pilot_name\nDD1234;J\xfcrgen, Sr.\n";
+        let result = decode_file_with_options(file, &options).unwrap();
+        assert_eq!(
+            result.records[0].as_ref().unwrap().pilot_name,
+            "J\u{fc}rgen, Sr."
+        );
+    }
+
+    #[test]
+    fn decoding_reader_uses_delimiter_and_encoding() {
+        let options = CsvOptions {
+            delimiter: b';',
+            encoding: encoding_rs::WINDOWS_1252,
+            ..Default::default()
+        };
+        let file = b"flarm_id;pilot_name\nDD1234;J\xfcrgen\n";
+        let result = decode_reader_with_options(&file[..], &options).unwrap();
+        assert_eq!(
+            result.records[0].as_ref().unwrap().pilot_name,
+            "J\u{fc}rgen"
+        );
+    }
+}
@@ -0,0 +1,200 @@
+use super::{Column, CsvOptions, Encoding, FREQUENCY_KEY};
+use crate::{Field, File};
+use std::borrow::Cow;
+use std::io::{Cursor, Write};
+use thiserror::Error;
+
+#[derive(Error, Debug)]
+pub enum EncodeError {
+    #[error(transparent)]
+    Io(#[from] std::io::Error),
+    #[error(transparent)]
+    Csv(#[from] csv::Error),
+    /// A value can not be represented in the [Encoding] of the
+    /// [CsvOptions].
+    #[error("unmappable character: {0:?}")]
+    UnmappableCharacter(char),
+}
+
+/// Encodes a CSV file with the default [CsvOptions].
+///
+/// # Examples
+///
+/// ```
+/// # use flarmnet::Record;
+/// let file = flarmnet::File {
+///     version: 123,
+///     records: vec![
+///         Record {
+///             flarm_id: "3EE3C7".parse().unwrap(),
+///             pilot_name: "Tobias Bieniek".to_string(),
+///             airfield: "EDKA".to_string(),
+///             plane_type: "LS6a".to_string(),
+///             registration: "D-0816".to_string(),
+///             call_sign: "SG".to_string(),
+///             frequency: Some("130.530".parse().unwrap()),
+///             extensions: Default::default(),
+///         }
+///     ],
+///     extensions: Default::default(),
+/// };
+///
+/// let result = flarmnet::csv::encode_file(&file).unwrap();
+/// assert_eq!(result, br#"flarm_id,pilot_name,airfield,plane_type,registration,call_sign,frequency
+/// 3EE3C7,Tobias Bieniek,EDKA,LS6a,D-0816,SG,130.530
+/// "#);
+/// ```
+pub fn encode_file(file: &File) -> Result<Vec<u8>, EncodeError> {
+    encode_file_with_options(file, &CsvOptions::default())
+}
+
+/// Encodes a CSV file with the delimiter and encoding of the given options.
+pub fn encode_file_with_options(file: &File, options: &CsvOptions) -> Result<Vec<u8>, EncodeError> {
+    let mut writer = Writer::with_options(Cursor::new(Vec::new()), options);
+    writer.write(file)?;
+    Ok(writer.into_inner().into_inner())
+}
+
+#[derive(Clone)]
+pub struct Writer<W: Write> {
+    writer: W,
+    delimiter: u8,
+    encoding: &'static Encoding,
+}
+
+impl<W: Write> Writer<W> {
+    pub fn new(inner: W) -> Self {
+        Self::with_options(inner, &CsvOptions::default())
+    }
+
+    /// Creates a writer using the delimiter and encoding of the given
+    /// options. The column names of the options are ignored.
+    pub fn with_options(inner: W, options: &CsvOptions) -> Self {
+        Self {
+            writer: inner,
+            delimiter: options.delimiter,
+            encoding: options.encoding,
+        }
+    }
+
+    /// Writes the header row and the records of the file, ignoring the file
+    /// version and the extensions, except for `csv.frequency`.
+    pub fn write(&mut self, file: &File) -> Result<(), EncodeError> {
+        let mut csv = csv::WriterBuilder::new()
+            .delimiter(self.delimiter)
+            .from_writer(Vec::new());
+
+        csv.write_record(Column::ALL.iter().map(|column| column.name()))?;
+        for record in &file.records {
+            let values = Column::ALL.iter().map(|column| match column {
+                Column::Field(Field::Frequency) if record.frequency.is_none() => {
+                    let frequency = record.extensions.get(FREQUENCY_KEY);
+                    Cow::Borrowed(frequency.map(String::as_str).unwrap_or_default())
+                }
+                column => column.get(record),
+            });
+            csv.write_record(values.map(|value| value.into_owned()))?;
+        }
+
+        let buffer = csv.into_inner().map_err(|error| error.into_error())?;
+        let buffer = String::from_utf8(buffer).expect("CSV writer input is valid UTF-8");
+        let (encoded, _, has_errors) = self.encoding.encode(&buffer);
+        if has_errors {
+            let unmappable = buffer
+                .chars()
+                .find(|char| self.encoding.encode(char.encode_utf8(&mut [0; 4])).2)
+                .expect("an unmappable character exists");
+            return Err(EncodeError::UnmappableCharacter(unmappable));
+        }
+
+        self.writer.write_all(&encoded)?;
+        Ok(())
+    }
+
+    pub fn into_inner(self) -> W {
+        self.writer
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{encode_file, encode_file_with_options};
+    use crate::csv::{CsvOptions, decode_file_with_options};
+    use crate::{File, Record};
+    use insta::assert_debug_snapshot;
+
+    fn make_file(pilot_name: &str) -> File {
+        File {
+            version: 0,
+            records: vec![Record {
+                flarm_id: "DD1234".parse().unwrap(),
+                pilot_name: pilot_name.to_string(),
+                airfield: String::new(),
+                plane_type: "Ka 6E".to_string(),
+                registration: "D-1234".to_string(),
+                call_sign: String::new(),
+                frequency: None,
+                extensions: Default::default(),
+            }],
+            extensions: Default::default(),
+        }
+    }
+
+    #[test]
+    fn encoding_quotes_values() {
+        let encoded = encode_file(&make_file("Doe, \"Jo\"")).unwrap();
+        assert_eq!(
+            String::from_utf8(encoded).unwrap().lines().nth(1),
+            Some(r#"DD1234,"Doe, ""Jo""",,Ka 6E,D-1234,,"#)
+        );
+    }
+
+    #[test]
+    fn encoding_round_trips_with_options() {
+        let options = CsvOptions {
+            delimiter: b'\t',
+            encoding: encoding_rs::WINDOWS_1252,
+            ..Default::default()
+        };
+        let file = make_file("Jürgen");
+
+        let encoded = encode_file_with_options(&file, &options).unwrap();
+        assert_eq!(
+            encoded.split(|it| *it == b'\n').nth(1),
+            Some(&b"DD1234\tJ\xfcrgen\t\tKa 6E\tD-1234\t\t"[..])
+        );
+
+        let decoded = decode_file_with_options(&encoded, &options).unwrap();
+        assert_eq!(decoded.records[0].as_ref().unwrap(), &file.records[0]);
+    }
+
+    #[test]
+    fn encoding_writes_back_invalid_frequencies() {
+        let mut file = make_file("");
+        let extensions = &mut file.records[0].extensions;
+        extensions.insert("csv.frequency".to_string(), "129.970".to_string());
+
+        let encoded = encode_file(&file).unwrap();
+        assert_eq!(
+            String::from_utf8(encoded.clone()).unwrap().lines().nth(1),
+            Some("DD1234,,,Ka 6E,D-1234,,129.970")
+        );
+
+        let decoded = decode_file_with_options(&encoded, &CsvOptions::default()).unwrap();
+        assert_eq!(decoded.records[0].as_ref().unwrap(), &file.records[0]);
+    }
+
+    #[test]
+    fn encoding_fails_for_unmappable_characters() {
+        let options = CsvOptions {
+            encoding: encoding_rs::WINDOWS_1252,
+            ..Default::default()
+        };
+        let result = encode_file_with_options(&make_file("Łukasz"), &options);
+        assert_debug_snapshot!(result.unwrap_err(), @r###"
+        UnmappableCharacter(
+            'Ł',
+        )
+        "###);
+    }
+}
@@ -0,0 +1,153 @@
+//! Decoder/Encoder for generic CSV files, e.g. exported from a spreadsheet.
+//!
+//! The [encode_file] function writes a header row followed by one row per
+//! [Record](crate::Record):
+//!
+//! ```text
+//! flarm_id,pilot_name,airfield,plane_type,registration,call_sign,frequency
+//! 3EE3C7,Tobias Bieniek,EDKA,LS6a,D-0816,SG,130.530
+//! ```
+//!
+//! The [decode_file] and [decode_reader] functions read such files back. The
+//! header row is used to find the columns, so they may be in any order, and
+//! unknown columns are ignored. Only the `flarm_id` column is required.
+//! Empty rows are skipped.
+//!
+//! [CsvOptions] can be used to read and write files with a different
+//! delimiter or character encoding, and to read files with different column
+//! names:
+//!
+//! ```
+//! use flarmnet::csv::{Column, CsvOptions, Encoding};
+//! use flarmnet::Field;
+//!
+//! let options = CsvOptions {
+//!     delimiter: b';',
+//!     encoding: Encoding::for_label(b"windows-1252").unwrap(),
+//!     columns: [
+//!         ("ID".to_string(), Column::FlarmId),
+//!         ("Kennzeichen".to_string(), Column::Field(Field::Registration)),
+//!     ]
+//!     .into(),
+//!     ..Default::default()
+//! };
+//!
+//! let file = b"ID;Kennzeichen;Pilot_Name\n3EE3C7;D-0816;Tobias Bieniek\n";
+//! let result = flarmnet::csv::decode_file_with_options(file, &options).unwrap();
+//! let record = result.records[0].as_ref().unwrap();
+//! assert_eq!(record.registration, "D-0816");
+//! assert_eq!(record.pilot_name, "Tobias Bieniek");
+//! ```
+//!
+//! The file version and the [Extensions](crate::Extensions) are not written
+//! to CSV files, except for the `csv.frequency` extension (see
+//! [CsvOptions::accept_invalid_frequencies]), and decoded files always have
+//! version `0`.
+
+mod decode;
+mod encode;
+
+pub use decode::*;
+pub use encode::*;
+pub use encoding_rs::Encoding;
+
+use crate::{Field, Record};
+use std::borrow::Cow;
+use std::collections::HashMap;
+use std::fmt;
+
+const FREQUENCY_KEY: &str = "csv.frequency";
+
+/// A column of a CSV file.
+#[derive(Debug, Eq, PartialEq, Hash, Clone, Copy)]
+pub enum Column {
+    FlarmId,
+    Field(Field),
+}
+
+impl Column {
+    /// All columns in the order they are written by the encoder.
+    pub const ALL: &'static [Column] = &[
+        Column::FlarmId,
+        Column::Field(Field::PilotName),
+        Column::Field(Field::Airfield),
+        Column::Field(Field::PlaneType),
+        Column::Field(Field::Registration),
+        Column::Field(Field::CallSign),
+        Column::Field(Field::Frequency),
+    ];
+
+    /// Returns the name that is used in the header row, e.g. `flarm_id`.
+    pub fn name(self) -> &'static str {
+        match self {
+            Column::FlarmId => "flarm_id",
+            Column::Field(field) => field.name(),
+        }
+    }
+
+    /// Returns the value of this column as it is written by the encoder.
+    pub fn get(self, record: &Record) -> Cow<'_, str> {
+        match self {
+            Column::FlarmId => Cow::Owned(record.flarm_id.to_string()),
+            Column::Field(field) => field.get(record),
+        }
+    }
+}
+
+impl fmt::Display for Column {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+/// Options for reading and writing CSV files.
+#[derive(Debug, Clone)]
+pub struct CsvOptions {
+    /// Field delimiter, `,` by default.
+    pub delimiter: u8,
+    /// Character encoding, UTF-8 by default.
+    ///
+    /// When decoding, a byte order mark takes precedence over this setting.
+    /// UTF-16 files are written as UTF-8, since encoding to UTF-16 is not
+    /// supported.
+    pub encoding: &'static Encoding,
+    /// Additional header names for the decoder, e.g. `Kennzeichen` for
+    /// [Field::Registration]. The default names (see [Column::name]) are
+    /// still recognized. Header names are compared case-insensitively.
+    pub columns: HashMap<String, Column>,
+    /// Accept frequencies that are not a valid [Frequency](crate::Frequency)
+    /// when decoding, `true` by default. The record is decoded with
+    /// `frequency: None` and the original value is stored in its
+    /// [Extensions](crate::Extensions) as `csv.frequency`, which the encoder
+    /// writes back if the record has no frequency. Otherwise the record fails
+    /// with [DecodeError::InvalidFrequency].
+    pub accept_invalid_frequencies: bool,
+}
+
+impl Default for CsvOptions {
+    fn default() -> Self {
+        Self {
+            delimiter: b',',
+            encoding: encoding_rs::UTF_8,
+            columns: HashMap::new(),
+            accept_invalid_frequencies: true,
+        }
+    }
+}
+
+impl CsvOptions {
+    /// Returns the column for the given header name, if any.
+    fn column(&self, name: &str) -> Option<Column> {
+        let name = name.trim();
+        self.columns
+            .iter()
+            .find(|(it, _)| it.trim().eq_ignore_ascii_case(name))
+            .map(|(_, column)| *column)
+            .or_else(|| {
+                Column::ALL
+                    .iter()
+                    .copied()
+                    .find(|column| column.name().eq_ignore_ascii_case(name))
+            })
+    }
+}
@@ -0,0 +1,234 @@
+use crate::{DecodedFile, File, FlarmId, Record};
+use std::collections::HashMap;
+
+/// In-memory lookup database for FlarmNet records.
+///
+/// The records are indexed by [FLARM ID](Record::flarm_id),
+/// [registration](Record::registration) and [call sign](Record::call_sign)
+/// when the database is created. Registrations and call signs are compared
+/// case-insensitively, and registrations also ignore any characters except
+/// letters and digits (e.g. `D-0816` matches `d0816`).
+///
+/// FlarmNet data may contain the same FLARM ID multiple times. In that case
+/// the first record is used for lookups, and the other ones are reported via
+/// [FlarmNetDb::duplicates].
+///
+/// # Examples
+///
+#[cfg_attr(feature = "xcsoar", doc = "```")]
+#[cfg_attr(not(feature = "xcsoar"), doc = "```ignore")]
+/// let content = std::fs::read("tests/fixtures/data.fln").unwrap();
+/// let file = flarmnet::decode_any(&content).unwrap();
+/// let db = flarmnet::FlarmNetDb::from(file);
+///
+/// let record = db.get("000000".parse().unwrap()).unwrap();
+/// assert_eq!(record.registration, "D-2188");
+///
+/// let records: Vec<_> = db.find_by_registration("d2188").collect();
+/// assert_eq!(records.len(), 1);
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct FlarmNetDb {
+    records: Vec<Record>,
+    by_flarm_id: HashMap<FlarmId, usize>,
+    by_registration: HashMap<String, Vec<usize>>,
+    by_call_sign: HashMap<String, Vec<usize>>,
+    duplicates: Vec<Duplicate>,
+}
+
+/// A record with a FLARM ID that was already used by a previous record.
+#[derive(Debug, Eq, PartialEq, Clone, Copy)]
+pub struct Duplicate {
+    pub flarm_id: FlarmId,
+    /// Index of the record that is used for lookups.
+    pub first: usize,
+    /// Index of the duplicate record.
+    pub duplicate: usize,
+}
+
+impl FlarmNetDb {
+    /// Creates a new database and indexes the records.
+    pub fn new(records: Vec<Record>) -> Self {
+        let mut db = Self {
+            records,
+            ..Default::default()
+        };
+
+        for (index, record) in db.records.iter().enumerate() {
+            if let Some(&first) = db.by_flarm_id.get(&record.flarm_id) {
+                db.duplicates.push(Duplicate {
+                    flarm_id: record.flarm_id,
+                    first,
+                    duplicate: index,
+                });
+                continue;
+            }
+            db.by_flarm_id.insert(record.flarm_id, index);
+
+            let registration = normalize_registration(&record.registration);
+            if !registration.is_empty() {
+                db.by_registration
+                    .entry(registration)
+                    .or_default()
+                    .push(index);
+            }
+
+            let call_sign = normalize_call_sign(&record.call_sign);
+            if !call_sign.is_empty() {
+                db.by_call_sign.entry(call_sign).or_default().push(index);
+            }
+        }
+
+        db
+    }
+
+    /// Returns all records, including duplicates, in their original order.
+    pub fn records(&self) -> &[Record] {
+        &self.records
+    }
+
+    pub fn len(&self) -> usize {
+        self.records.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.records.is_empty()
+    }
+
+    /// Returns the record with the given FLARM ID.
+    pub fn get(&self, flarm_id: FlarmId) -> Option<&Record> {
+        let index = *self.by_flarm_id.get(&flarm_id)?;
+        Some(&self.records[index])
+    }
+
+    /// Returns all records with the given registration.
+    ///
+    /// A registration may be used by multiple records, e.g. if an aircraft
+    /// has more than one FLARM device.
+    pub fn find_by_registration(
+        &self,
+        registration: &str,
+    ) -> impl Iterator<Item = &Record> + use<'_> {
+        let key = normalize_registration(registration);
+        self.lookup(&self.by_registration, &key)
+    }
+
+    /// Returns all records with the given call sign (competition ID).
+    ///
+    /// Call signs are not unique, so this may return multiple records.
+    pub fn find_by_call_sign(&self, call_sign: &str) -> impl Iterator<Item = &Record> + use<'_> {
+        let key = normalize_call_sign(call_sign);
+        self.lookup(&self.by_call_sign, &key)
+    }
+
+    /// Returns the records that have been skipped while indexing because
+    /// their FLARM ID was already used by a previous record.
+    pub fn duplicates(&self) -> &[Duplicate] {
+        &self.duplicates
+    }
+
+    fn lookup<'a>(
+        &'a self,
+        index: &'a HashMap<String, Vec<usize>>,
+        key: &str,
+    ) -> impl Iterator<Item = &'a Record> + use<'a> {
+        let indices = index.get(key).map(Vec::as_slice).unwrap_or_default();
+        indices.iter().map(|&index| &self.records[index])
+    }
+}
+
+impl From<File> for FlarmNetDb {
+    fn from(file: File) -> Self {
+        Self::new(file.records)
+    }
+}
+
+impl From<DecodedFile> for FlarmNetDb {
+    /// Creates a new database, skipping all records that could not be
+    /// decoded.
+    fn from(file: DecodedFile) -> Self {
+        Self::from(file.into_file())
+    }
+}
+
+fn normalize_registration(registration: &str) -> String {
+    registration
+        .chars()
+        .filter(|c| c.is_alphanumeric())
+        .flat_map(char::to_uppercase)
+        .collect()
+}
+
+fn normalize_call_sign(call_sign: &str) -> String {
+    call_sign.trim().to_uppercase()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Duplicate, FlarmNetDb};
+    use crate::test_util::make_record;
+
+    #[test]
+    fn lookup_by_flarm_id() {
+        let db = FlarmNetDb::new(vec![
+            make_record("3EE3C7", "D-0816", "SG"),
+            make_record("DD1234", "D-KABC", "BC"),
+        ]);
+
+        let record = db.get("dd1234".parse().unwrap()).unwrap();
+        assert_eq!(record.registration, "D-KABC");
+        assert!(db.get("000001".parse().unwrap()).is_none());
+    }
+
+    #[test]
+    fn lookup_by_registration_ignores_case_and_format() {
+        let db = FlarmNetDb::new(vec![
+            make_record("3EE3C7", "D-0816", "SG"),
+            make_record("3EE3C8", "D-0816", "SG"),
+            make_record("DD1234", "D-KABC", "BC"),
+        ]);
+
+        let ids: Vec<_> = db
+            .find_by_registration("d 0816")
+            .map(|it| it.flarm_id.to_string())
+            .collect();
+        assert_eq!(ids, ["3EE3C7", "3EE3C8"]);
+
+        assert_eq!(db.find_by_registration("DKABC").count(), 1);
+        assert_eq!(db.find_by_registration("D-KXYZ").count(), 0);
+        assert_eq!(db.find_by_registration("").count(), 0);
+    }
+
+    #[test]
+    fn lookup_by_call_sign_returns_all_matches() {
+        let db = FlarmNetDb::new(vec![
+            make_record("3EE3C7", "D-0816", "SG"),
+            make_record("DD1234", "D-KABC", "sg"),
+            make_record("DD1235", "D-KABD", ""),
+        ]);
+
+        assert_eq!(db.find_by_call_sign(" SG ").count(), 2);
+        assert_eq!(db.find_by_call_sign("").count(), 0);
+    }
+
+    #[test]
+    fn duplicates_are_reported() {
+        let db = FlarmNetDb::new(vec![
+            make_record("3EE3C7", "D-0816", "SG"),
+            make_record("DD1234", "D-KABC", "BC"),
+            make_record("3EE3C7", "D-1234", "XY"),
+        ]);
+
+        assert_eq!(db.len(), 3);
+        assert_eq!(db.get("3EE3C7".parse().unwrap()).unwrap().call_sign, "SG");
+        assert_eq!(db.find_by_registration("D-1234").count(), 0);
+        assert_eq!(
+            db.duplicates(),
+            [Duplicate {
+                flarm_id: "3EE3C7".parse().unwrap(),
+                first: 0,
+                duplicate: 2,
+            }]
+        );
+    }
+}
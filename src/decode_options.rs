@@ -0,0 +1,127 @@
+/// Options to control how strictly the decoders validate their input.
+///
+/// The [Default] options correspond to the behavior of the `decode_file()`
+/// functions of the format modules, while [STRICT](DecodeOptions::STRICT)
+/// rejects anything that the official FlarmNet files would not contain and
+/// [LENIENT](DecodeOptions::LENIENT) accepts as much as possible.
+///
+/// Each option only applies to the formats that are listed in its
+/// documentation.
+///
+/// # Examples
+///
+#[cfg_attr(feature = "xcsoar", doc = "```")]
+#[cfg_attr(not(feature = "xcsoar"), doc = "```ignore")]
+/// # use flarmnet::DecodeOptions;
+/// let line = "3030303030304dfc6c6c6572202020202020202020202020202020442d3231383820202020202020202020202020202041534b2d3133202020202020202020202020202020442d32313838202020203132332e313530";
+/// let short_line = &line[..line.len() - 14];
+///
+/// assert!(flarmnet::xcsoar::decode_record(short_line).is_err());
+///
+/// let options = DecodeOptions::LENIENT;
+/// let record = flarmnet::xcsoar::decode_record_with_options(short_line, &options).unwrap();
+/// assert_eq!(record.registration, "D-2188");
+/// assert_eq!(record.frequency, None);
+/// ```
+#[derive(Debug, Eq, PartialEq, Hash, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
+pub struct DecodeOptions {
+    /// Accept records that are shorter than the fixed record length, treating
+    /// the missing characters as spaces.
+    ///
+    /// Applies to: XCSoar
+    pub accept_short_lines: bool,
+    /// Accept files that end before all records announced in the header have
+    /// been read. The missing records are reported as
+    /// [UnexpectedEof](crate::tdb::DecodeError::UnexpectedEof) record error
+    /// instead of failing the whole file.
+    ///
+    /// Applies to: TDB
+    pub accept_truncated_files: bool,
+    /// Accept `FLARMDATA` elements without some of the known child elements,
+    /// using empty values instead.
+    ///
+    /// Applies to: LX
+    pub accept_missing_elements: bool,
+    /// Accept files without a file version, using version `0` instead.
+    ///
+    /// Applies to: LX, XCSoar
+    pub accept_missing_version: bool,
+    /// Accept FLARM IDs that are not written as exactly six hex digits in the
+    /// letter case of the format, i.e. lowercase FLARM IDs in LX files and
+    /// uppercase FLARM IDs in XCSoar files.
+    ///
+    /// Applies to: LX, XCSoar
+    pub accept_non_canonical_ids: bool,
+    /// Accept frequencies outside of the air band or off the channel grid
+    /// (see [Frequency](crate::Frequency)). The record is decoded with
+    /// `frequency: None` and the original value is stored in its
+    /// [Extensions](crate::Extensions), so that the encoders can write it
+    /// back. Otherwise the record fails with an `InvalidFrequency` error.
+    ///
+    /// Applies to: LX, TDB, XCSoar
+    pub accept_invalid_frequencies: bool,
+    /// Accept files whose FLARM ID index is not sorted. Otherwise the whole
+    /// file fails with [UnsortedIndex](crate::tdb::DecodeError::UnsortedIndex).
+    ///
+    /// Applies to: TDB
+    pub accept_unsorted_index: bool,
+    /// Accept non-zero padding bytes between the FLARM ID index and the
+    /// records. They are stored in the file [Extensions](crate::Extensions),
+    /// so that the encoder can write them back. Otherwise the whole file
+    /// fails with [InvalidPadding](crate::tdb::DecodeError::InvalidPadding).
+    ///
+    /// Applies to: TDB
+    pub accept_non_zero_padding: bool,
+    /// Remove leading and trailing whitespace from the field values.
+    ///
+    /// XCSoar fields are padded with spaces, so they are trimmed in any case.
+    ///
+    /// Applies to: LX, TDB
+    pub trim_whitespace: bool,
+}
+
+impl DecodeOptions {
+    /// Rejects all deviations from the official file formats.
+    pub const STRICT: DecodeOptions = DecodeOptions {
+        accept_short_lines: false,
+        accept_truncated_files: false,
+        accept_missing_elements: false,
+        accept_missing_version: false,
+        accept_non_canonical_ids: false,
+        accept_invalid_frequencies: false,
+        accept_unsorted_index: false,
+        accept_non_zero_padding: false,
+        trim_whitespace: false,
+    };
+
+    /// Accepts as much as possible.
+    pub const LENIENT: DecodeOptions = DecodeOptions {
+        accept_short_lines: true,
+        accept_truncated_files: true,
+        accept_missing_elements: true,
+        accept_missing_version: true,
+        accept_non_canonical_ids: true,
+        accept_invalid_frequencies: true,
+        accept_unsorted_index: true,
+        accept_non_zero_padding: true,
+        trim_whitespace: true,
+    };
+}
+
+impl Default for DecodeOptions {
+    fn default() -> Self {
+        Self {
+            accept_short_lines: false,
+            accept_truncated_files: false,
+            accept_missing_elements: true,
+            accept_missing_version: false,
+            accept_non_canonical_ids: true,
+            accept_invalid_frequencies: true,
+            accept_unsorted_index: false,
+            accept_non_zero_padding: false,
+            trim_whitespace: false,
+        }
+    }
+}
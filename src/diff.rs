@@ -0,0 +1,290 @@
+use crate::{Field, File, FlarmId, Record};
+use std::collections::BTreeMap;
+
+/// Options for [diff_with_options].
+#[derive(Debug, Default, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
+pub struct DiffOptions {
+    /// Ignore field changes where one value is the other one truncated to the
+    /// field width of one of the enabled formats.
+    ///
+    /// This is useful to compare files of different formats, since e.g. the
+    /// XCSoar format truncates call signs to 3 characters and registrations
+    /// to 7 characters, and the TDB format truncates all text fields to 15
+    /// bytes.
+    pub ignore_truncation: bool,
+}
+
+/// Differences between two FlarmNet files, see [diff].
+#[derive(Debug, Eq, PartialEq, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Diff {
+    pub old_version: u32,
+    pub new_version: u32,
+    /// Records that only exist in the new file, sorted by FLARM ID.
+    pub added: Vec<Record>,
+    /// Records that only exist in the old file, sorted by FLARM ID.
+    pub removed: Vec<Record>,
+    /// Records that exist in both files with different field values, sorted
+    /// by FLARM ID.
+    pub changed: Vec<ChangedRecord>,
+}
+
+impl Diff {
+    /// Returns the difference between the new and the old file version.
+    pub fn version_delta(&self) -> i64 {
+        i64::from(self.new_version) - i64::from(self.old_version)
+    }
+
+    /// Returns `true` if no records have been added, removed or changed.
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.removed.is_empty() && self.changed.is_empty()
+    }
+}
+
+/// A record that exists in both files with different field values.
+#[derive(Debug, Eq, PartialEq, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ChangedRecord {
+    pub flarm_id: FlarmId,
+    pub changes: Vec<FieldChange>,
+}
+
+/// The old and new value of a changed [Field].
+#[derive(Debug, Eq, PartialEq, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct FieldChange {
+    pub field: Field,
+    pub old: String,
+    pub new: String,
+}
+
+/// Compares two FlarmNet files, matching the records by FLARM ID.
+///
+/// Only the [fields](Field) of the records are compared, the
+/// [extensions](Record::extensions) are ignored. If a file contains the same
+/// FLARM ID multiple times, only the first record is used.
+///
+/// # Examples
+///
+#[cfg_attr(feature = "xcsoar", doc = "```")]
+#[cfg_attr(not(feature = "xcsoar"), doc = "```ignore")]
+/// let old = std::fs::read("tests/fixtures/data.fln").unwrap();
+/// let old = flarmnet::decode_any(&old).unwrap().into_file();
+///
+/// let mut new = old.clone();
+/// new.version += 1;
+/// new.records[0].plane_type = "ASK-21".to_string();
+/// new.records.pop();
+///
+/// let diff = flarmnet::diff(&old, &new);
+/// assert_eq!(diff.version_delta(), 1);
+/// assert_eq!(diff.added.len(), 0);
+/// assert_eq!(diff.removed.len(), 1);
+/// assert_eq!(diff.changed.len(), 1);
+/// assert_eq!(diff.changed[0].changes[0].old, "ASK-13");
+/// assert_eq!(diff.changed[0].changes[0].new, "ASK-21");
+/// ```
+pub fn diff(old: &File, new: &File) -> Diff {
+    diff_with_options(old, new, &DiffOptions::default())
+}
+
+/// Compares two FlarmNet files like [diff], but with custom [DiffOptions].
+pub fn diff_with_options(old: &File, new: &File, options: &DiffOptions) -> Diff {
+    let old_records = index_by_flarm_id(&old.records);
+    let new_records = index_by_flarm_id(&new.records);
+
+    let mut added = Vec::new();
+    let mut changed = Vec::new();
+    for (flarm_id, new_record) in &new_records {
+        let Some(old_record) = old_records.get(flarm_id) else {
+            added.push((*new_record).clone());
+            continue;
+        };
+
+        let changes = diff_records(old_record, new_record, options);
+        if !changes.is_empty() {
+            changed.push(ChangedRecord {
+                flarm_id: *flarm_id,
+                changes,
+            });
+        }
+    }
+
+    let removed = old_records
+        .iter()
+        .filter(|(flarm_id, _)| !new_records.contains_key(flarm_id))
+        .map(|(_, record)| (*record).clone())
+        .collect();
+
+    Diff {
+        old_version: old.version,
+        new_version: new.version,
+        added,
+        removed,
+        changed,
+    }
+}
+
+fn index_by_flarm_id(records: &[Record]) -> BTreeMap<FlarmId, &Record> {
+    let mut index = BTreeMap::new();
+    for record in records {
+        index.entry(record.flarm_id).or_insert(record);
+    }
+    index
+}
+
+fn diff_records(old: &Record, new: &Record, options: &DiffOptions) -> Vec<FieldChange> {
+    Field::ALL
+        .iter()
+        .filter_map(|&field| {
+            let old = field.get(old);
+            let new = field.get(new);
+            if old == new || (options.ignore_truncation && is_truncation(field, &old, &new)) {
+                return None;
+            }
+
+            Some(FieldChange {
+                field,
+                old: old.into_owned(),
+                new: new.into_owned(),
+            })
+        })
+        .collect()
+}
+
+/// Returns `true` if one of the (trimmed) values is the other one truncated
+/// to the field width of one of the enabled formats.
+fn is_truncation(field: Field, a: &str, b: &str) -> bool {
+    let (a, b) = (a.trim_end(), b.trim_end());
+    let (shorter, longer) = if a.len() < b.len() { (a, b) } else { (b, a) };
+    !shorter.is_empty() && truncations(field, longer).any(|it| it.trim_end() == shorter)
+}
+
+/// Returns the value as it would be written by the encoders of the enabled
+/// formats that truncate the field.
+#[cfg_attr(not(any(feature = "tdb", feature = "xcsoar")), allow(unused_variables))]
+fn truncations(field: Field, value: &str) -> impl Iterator<Item = &str> {
+    #[cfg(feature = "tdb")]
+    let tdb = match field {
+        Field::Frequency => None,
+        _ => {
+            // one byte is reserved for the null terminator
+            let length = crate::tdb::consts::STRING_FIELD_SIZE - 1;
+            Some(&value[..value.floor_char_boundary(length)])
+        }
+    };
+    #[cfg(not(feature = "tdb"))]
+    let tdb = None;
+
+    #[cfg(feature = "xcsoar")]
+    let xcsoar = {
+        use crate::xcsoar::fields::*;
+        let length = match field {
+            Field::PilotName => PILOT_NAME_LENGTH,
+            Field::Airfield => AIRFIELD_LENGTH,
+            Field::PlaneType => PLANE_TYPE_LENGTH,
+            Field::Registration => REGISTRATION_LENGTH,
+            Field::CallSign => CALL_SIGN_LENGTH,
+            Field::Frequency => FREQUENCY_LENGTH,
+        };
+        // the length is counted in characters
+        let end = value.char_indices().nth(length).map(|(i, _)| i);
+        Some(&value[..end.unwrap_or(value.len())])
+    };
+    #[cfg(not(feature = "xcsoar"))]
+    let xcsoar = None;
+
+    tdb.into_iter().chain(xcsoar)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{DiffOptions, diff, diff_with_options};
+    use crate::test_util::{make_file, make_record};
+    use insta::assert_debug_snapshot;
+
+    #[test]
+    fn diff_of_identical_files_is_empty() {
+        let file = make_file(1, vec![make_record("3EE3C7", "D-0816", "SG")]);
+        let diff = diff(&file, &file);
+        assert!(diff.is_empty());
+        assert_eq!(diff.version_delta(), 0);
+    }
+
+    #[test]
+    fn diff_reports_added_removed_and_changed_records() {
+        let old = make_file(
+            5,
+            vec![
+                make_record("3EE3C7", "D-0816", "SG"),
+                make_record("DD1234", "D-KABC", "BC"),
+            ],
+        );
+        let mut changed = make_record("3EE3C7", "D-0817", "SG");
+        changed.frequency = Some("123.500".parse().unwrap());
+        let new = make_file(3, vec![changed, make_record("DD5678", "D-KXYZ", "XY")]);
+
+        let diff = diff(&old, &new);
+        assert_eq!(diff.version_delta(), -2);
+        assert_debug_snapshot!(diff.added.iter().map(|it| it.flarm_id).collect::<Vec<_>>(), @r###"
+        [
+            FlarmId(
+                DD5678,
+            ),
+        ]
+        "###);
+        assert_debug_snapshot!(diff.removed.iter().map(|it| it.flarm_id).collect::<Vec<_>>(), @r###"
+        [
+            FlarmId(
+                DD1234,
+            ),
+        ]
+        "###);
+        assert_debug_snapshot!(diff.changed, @r###"
+        [
+            ChangedRecord {
+                flarm_id: FlarmId(
+                    3EE3C7,
+                ),
+                changes: [
+                    FieldChange {
+                        field: Registration,
+                        old: "D-0816",
+                        new: "D-0817",
+                    },
+                    FieldChange {
+                        field: Frequency,
+                        old: "",
+                        new: "123.500",
+                    },
+                ],
+            },
+        ]
+        "###);
+    }
+
+    #[test]
+    #[cfg(feature = "xcsoar")]
+    fn diff_can_ignore_truncation() {
+        let old = make_file(1, vec![make_record("3EE3C7", "D-KABCD", "SGX")]);
+        let new = make_file(1, vec![make_record("3EE3C7", "D-KABCDE", "SGX12")]);
+
+        assert_eq!(diff(&old, &new).changed[0].changes.len(), 2);
+
+        let options = DiffOptions {
+            ignore_truncation: true,
+        };
+        assert!(diff_with_options(&old, &new, &options).is_empty());
+
+        // removing a value is not a truncation
+        let new = make_file(1, vec![make_record("3EE3C7", "D-KABCD", "")]);
+        assert_eq!(diff_with_options(&old, &new, &options).changed.len(), 1);
+
+        // prefixes shorter than the field width are not a truncation
+        let new = make_file(1, vec![make_record("3EE3C7", "D-KABC", "SG")]);
+        let diff = diff_with_options(&old, &new, &options);
+        assert_eq!(diff.changed[0].changes.len(), 2);
+    }
+}
@@ -0,0 +1,149 @@
+use crate::Format;
+use thiserror::Error;
+
+/// The kind of operation that failed.
+#[derive(Debug, Eq, PartialEq, Hash, Clone, Copy)]
+pub enum Operation {
+    Decode,
+    Encode,
+}
+
+/// Error type wrapping the errors of all format modules.
+///
+/// The [format](Error::format) and [operation](Error::operation) that failed
+/// can be queried from the error, while the original format-specific error is
+/// available via [std::error::Error::source].
+#[derive(Error, Debug)]
+pub enum Error {
+    #[error("unknown file format")]
+    UnknownFormat,
+    #[cfg(feature = "lx")]
+    #[error("failed to decode LX file")]
+    LxDecode(#[from] crate::lx::DecodeError),
+    #[cfg(feature = "lx")]
+    #[error("failed to encode LX file")]
+    LxEncode(#[from] crate::lx::EncodeError),
+    #[cfg(feature = "tdb")]
+    #[error("failed to decode TDB file")]
+    TdbDecode(#[from] crate::tdb::DecodeError),
+    #[cfg(feature = "tdb")]
+    #[error("failed to encode TDB file")]
+    TdbEncode(#[from] crate::tdb::EncodeError),
+    #[cfg(feature = "xcsoar")]
+    #[error("failed to decode XCSoar file")]
+    XCSoarDecode(#[from] crate::xcsoar::DecodeError),
+    #[cfg(feature = "xcsoar")]
+    #[error("failed to encode XCSoar file")]
+    XCSoarEncode(#[from] crate::xcsoar::EncodeError),
+}
+
+impl Error {
+    /// Returns the format that failed, or `None` if the format could not be
+    /// detected.
+    pub fn format(&self) -> Option<Format> {
+        match self {
+            Error::UnknownFormat => None,
+            #[cfg(feature = "lx")]
+            Error::LxDecode(_) | Error::LxEncode(_) => Some(Format::Lx),
+            #[cfg(feature = "tdb")]
+            Error::TdbDecode(_) | Error::TdbEncode(_) => Some(Format::Tdb),
+            #[cfg(feature = "xcsoar")]
+            Error::XCSoarDecode(_) | Error::XCSoarEncode(_) => Some(Format::XCSoar),
+        }
+    }
+
+    /// Returns the operation that failed.
+    pub fn operation(&self) -> Operation {
+        match self {
+            Error::UnknownFormat => Operation::Decode,
+            #[cfg(feature = "lx")]
+            Error::LxDecode(_) => Operation::Decode,
+            #[cfg(feature = "lx")]
+            Error::LxEncode(_) => Operation::Encode,
+            #[cfg(feature = "tdb")]
+            Error::TdbDecode(_) => Operation::Decode,
+            #[cfg(feature = "tdb")]
+            Error::TdbEncode(_) => Operation::Encode,
+            #[cfg(feature = "xcsoar")]
+            Error::XCSoarDecode(_) => Operation::Decode,
+            #[cfg(feature = "xcsoar")]
+            Error::XCSoarEncode(_) => Operation::Encode,
+        }
+    }
+}
+
+/// Delegates to the format-specific decode errors, which point into the
+/// decoded file.
+#[cfg(feature = "miette")]
+impl miette::Diagnostic for Error {
+    fn code<'a>(&'a self) -> Option<Box<dyn std::fmt::Display + 'a>> {
+        if matches!(self, Error::UnknownFormat) {
+            return Some(Box::new("flarmnet::unknown_format"));
+        }
+        self.decode_error()?.code()
+    }
+
+    fn help<'a>(&'a self) -> Option<Box<dyn std::fmt::Display + 'a>> {
+        self.decode_error()?.help()
+    }
+
+    fn labels(&self) -> Option<Box<dyn Iterator<Item = miette::LabeledSpan> + '_>> {
+        self.decode_error()?.labels()
+    }
+}
+
+#[cfg(feature = "miette")]
+impl Error {
+    fn decode_error(&self) -> Option<&dyn miette::Diagnostic> {
+        match self {
+            #[cfg(feature = "lx")]
+            Error::LxDecode(error) => Some(error),
+            #[cfg(feature = "tdb")]
+            Error::TdbDecode(error) => Some(error),
+            #[cfg(feature = "xcsoar")]
+            Error::XCSoarDecode(error) => Some(error),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Error, Operation};
+    use std::error::Error as _;
+
+    fn assert_send_sync_static<T: Send + Sync + 'static>() {}
+
+    #[test]
+    fn error_is_send_sync_static() {
+        assert_send_sync_static::<Error>();
+    }
+
+    #[test]
+    #[cfg(feature = "xcsoar")]
+    fn error_carries_format_and_operation_of_decode_errors() {
+        let error = Error::from(crate::xcsoar::DecodeError::MissingVersion);
+        assert_eq!(error.format(), Some(crate::Format::XCSoar));
+        assert_eq!(error.operation(), Operation::Decode);
+        assert_eq!(error.to_string(), "failed to decode XCSoar file");
+        assert_eq!(error.source().unwrap().to_string(), "missing file version");
+    }
+
+    #[test]
+    #[cfg(feature = "tdb")]
+    fn error_carries_format_and_operation_of_encode_errors() {
+        let io_error = std::io::Error::other("disk full");
+        let error = Error::from(crate::tdb::EncodeError::Io(io_error));
+        assert_eq!(error.format(), Some(crate::Format::Tdb));
+        assert_eq!(error.operation(), Operation::Encode);
+        assert_eq!(error.source().unwrap().to_string(), "disk full");
+    }
+
+    #[test]
+    fn unknown_format_error_has_no_format() {
+        let error = Error::UnknownFormat;
+        assert_eq!(error.format(), None);
+        assert_eq!(error.operation(), Operation::Decode);
+        assert!(error.source().is_none());
+    }
+}
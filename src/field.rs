@@ -0,0 +1,83 @@
+use crate::Record;
+use std::borrow::Cow;
+use std::fmt;
+
+/// The data fields of a [Record], excluding the FLARM ID and the extensions.
+#[derive(Debug, Eq, PartialEq, Ord, PartialOrd, Hash, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
+pub enum Field {
+    PilotName,
+    Airfield,
+    PlaneType,
+    Registration,
+    CallSign,
+    Frequency,
+}
+
+impl Field {
+    /// All fields in the order of the [Record] struct.
+    pub const ALL: &'static [Field] = &[
+        Field::PilotName,
+        Field::Airfield,
+        Field::PlaneType,
+        Field::Registration,
+        Field::CallSign,
+        Field::Frequency,
+    ];
+
+    /// Returns the name of the corresponding [Record] field, e.g.
+    /// `pilot_name`.
+    pub fn name(self) -> &'static str {
+        match self {
+            Field::PilotName => "pilot_name",
+            Field::Airfield => "airfield",
+            Field::PlaneType => "plane_type",
+            Field::Registration => "registration",
+            Field::CallSign => "call_sign",
+            Field::Frequency => "frequency",
+        }
+    }
+
+    /// Returns the value of this field as a string.
+    ///
+    /// A missing frequency is returned as an empty string.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use flarmnet::{Field, Record};
+    /// let record = Record {
+    ///     flarm_id: "3EE3C7".parse().unwrap(),
+    ///     pilot_name: "Tobias Bieniek".to_string(),
+    ///     airfield: "EDKA".to_string(),
+    ///     plane_type: "LS6a".to_string(),
+    ///     registration: "D-0816".to_string(),
+    ///     call_sign: "SG".to_string(),
+    ///     frequency: Some("130.530".parse().unwrap()),
+    ///     extensions: Default::default(),
+    /// };
+    ///
+    /// assert_eq!(Field::Registration.get(&record), "D-0816");
+    /// assert_eq!(Field::Frequency.get(&record), "130.530");
+    /// ```
+    pub fn get(self, record: &Record) -> Cow<'_, str> {
+        match self {
+            Field::PilotName => Cow::Borrowed(&record.pilot_name),
+            Field::Airfield => Cow::Borrowed(&record.airfield),
+            Field::PlaneType => Cow::Borrowed(&record.plane_type),
+            Field::Registration => Cow::Borrowed(&record.registration),
+            Field::CallSign => Cow::Borrowed(&record.call_sign),
+            Field::Frequency => match record.frequency {
+                Some(frequency) => Cow::Owned(frequency.to_string()),
+                None => Cow::Borrowed(""),
+            },
+        }
+    }
+}
+
+impl fmt::Display for Field {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
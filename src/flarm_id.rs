@@ -0,0 +1,158 @@
+use std::fmt;
+use std::str::FromStr;
+use thiserror::Error;
+
+/// 24-bit FLARM radio ID.
+///
+/// The canonical string representation is six uppercase hex digits (e.g.
+/// `3EE3C7`), but parsing also accepts lowercase and shorter hex strings.
+///
+/// # Examples
+///
+/// ```
+/// # use flarmnet::FlarmId;
+/// let id: FlarmId = "3ee3c7".parse().unwrap();
+/// assert_eq!(id.value(), 0x3EE3C7);
+/// assert_eq!(id.to_string(), "3EE3C7");
+/// assert_eq!(format!("{id:06x}"), "3ee3c7");
+///
+/// assert!("1000000".parse::<FlarmId>().is_err());
+/// ```
+#[derive(Eq, PartialEq, Ord, PartialOrd, Hash, Clone, Copy)]
+pub struct FlarmId(u32);
+
+#[derive(Error, Debug, Eq, PartialEq, Clone)]
+#[error("invalid FLARM id: {0}")]
+pub struct ParseFlarmIdError(String);
+
+impl FlarmId {
+    /// The largest valid FLARM ID.
+    pub const MAX: FlarmId = FlarmId(0xFFFFFF);
+
+    /// Creates a FLARM ID from its numeric value, or returns `None` if the
+    /// value does not fit into 24 bits.
+    pub const fn new(value: u32) -> Option<FlarmId> {
+        if value <= Self::MAX.0 {
+            Some(FlarmId(value))
+        } else {
+            None
+        }
+    }
+
+    /// Returns the numeric value of the FLARM ID.
+    pub const fn value(self) -> u32 {
+        self.0
+    }
+}
+
+impl FromStr for FlarmId {
+    type Err = ParseFlarmIdError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        // `from_str_radix()` would also accept a leading `+` sign
+        if s.is_empty() || s.len() > 6 || !s.bytes().all(|b| b.is_ascii_hexdigit()) {
+            return Err(ParseFlarmIdError(s.to_string()));
+        }
+
+        let value = u32::from_str_radix(s, 16).map_err(|_| ParseFlarmIdError(s.to_string()))?;
+        Ok(FlarmId(value))
+    }
+}
+
+impl fmt::Display for FlarmId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:06X}", self.0)
+    }
+}
+
+impl fmt::Debug for FlarmId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("FlarmId")
+            .field(&format_args!("{self}"))
+            .finish()
+    }
+}
+
+impl fmt::LowerHex for FlarmId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::LowerHex::fmt(&self.0, f)
+    }
+}
+
+impl fmt::UpperHex for FlarmId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::UpperHex::fmt(&self.0, f)
+    }
+}
+
+impl From<FlarmId> for u32 {
+    fn from(id: FlarmId) -> u32 {
+        id.0
+    }
+}
+
+#[cfg(feature = "serde")]
+impl serde::Serialize for FlarmId {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for FlarmId {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let s = <std::borrow::Cow<'de, str>>::deserialize(deserializer)?;
+        s.parse().map_err(serde::de::Error::custom)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::FlarmId;
+    use insta::assert_debug_snapshot;
+
+    #[test]
+    fn parsing_works() {
+        assert_eq!("000000".parse(), Ok(FlarmId(0)));
+        assert_eq!("3EE3C7".parse(), Ok(FlarmId(0x3EE3C7)));
+        assert_eq!("c0ffee".parse(), Ok(FlarmId(0xC0FFEE)));
+        assert_eq!("f".parse(), Ok(FlarmId(0xF)));
+        assert_eq!("FFFFFF".parse(), Ok(FlarmId::MAX));
+    }
+
+    #[test]
+    fn parsing_fails_for_invalid_ids() {
+        assert_debug_snapshot!("".parse::<FlarmId>().unwrap_err(), @r###"
+        ParseFlarmIdError(
+            "",
+        )
+        "###);
+        assert!("ZZZZZZ".parse::<FlarmId>().is_err());
+        assert!("+12345".parse::<FlarmId>().is_err());
+        assert!(" 12345".parse::<FlarmId>().is_err());
+        assert!("1000000".parse::<FlarmId>().is_err());
+        assert!("0000000".parse::<FlarmId>().is_err());
+    }
+
+    #[test]
+    fn new_is_limited_to_24_bits() {
+        assert_eq!(FlarmId::new(0xFFFFFF), Some(FlarmId::MAX));
+        assert_eq!(FlarmId::new(0x1000000), None);
+    }
+
+    #[test]
+    fn formatting_works() {
+        let id = FlarmId(0xC0FFEE);
+        assert_eq!(id.to_string(), "C0FFEE");
+        assert_eq!(FlarmId(0xF).to_string(), "00000F");
+        assert_eq!(format!("{id:?}"), "FlarmId(C0FFEE)");
+        assert_eq!(format!("{:06x}", FlarmId(0xF)), "00000f");
+    }
+
+    #[test]
+    fn ordering_uses_numeric_value() {
+        let mut ids = vec![FlarmId(0xF), FlarmId(0xA0), FlarmId(0x1)];
+        ids.sort();
+        assert_eq!(ids, vec![FlarmId(0x1), FlarmId(0xF), FlarmId(0xA0)]);
+    }
+}
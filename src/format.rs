@@ -0,0 +1,244 @@
+#[cfg(any(feature = "lx", feature = "tdb", feature = "xcsoar"))]
+use crate::Codec;
+use crate::{DecodeOptions, DecodedFile, EncodeReport, Error, File};
+
+/// FlarmNet file formats supported by this crate.
+///
+/// Variants are only available if the corresponding cargo feature is
+/// enabled.
+#[derive(Debug, Eq, PartialEq, Hash, Clone, Copy)]
+pub enum Format {
+    /// LXNav/Naviter format, see [crate::lx].
+    #[cfg(feature = "lx")]
+    Lx,
+    /// Air Avionics format, see [crate::tdb].
+    #[cfg(feature = "tdb")]
+    Tdb,
+    /// LXNavigation/XCSoar/WinPilot/LK8000/ClearNav format, see [crate::xcsoar].
+    #[cfg(feature = "xcsoar")]
+    XCSoar,
+}
+
+impl Format {
+    /// All formats that are enabled via cargo features.
+    pub const ALL: &'static [Format] = &[
+        #[cfg(feature = "lx")]
+        Format::Lx,
+        #[cfg(feature = "tdb")]
+        Format::Tdb,
+        #[cfg(feature = "xcsoar")]
+        Format::XCSoar,
+    ];
+
+    /// Detects the format of a FlarmNet file by looking at its first bytes.
+    ///
+    /// - TDB files start with the [magic number](crate::tdb) `08 d5 19 87`.
+    /// - LX files start with `<?xml`, shifted by one byte (`=@ynm`).
+    /// - XCSoar files start with a line of exactly six hex digits.
+    ///
+    /// # Examples
+    ///
+    #[cfg_attr(all(feature = "lx", feature = "xcsoar"), doc = "```")]
+    #[cfg_attr(not(all(feature = "lx", feature = "xcsoar")), doc = "```ignore")]
+    /// # use flarmnet::Format;
+    /// assert_eq!(Format::detect(b"006fb0\n"), Some(Format::XCSoar));
+    /// assert_eq!(Format::detect(b"=@ynm!wfstjpo>#2/1#"), Some(Format::Lx));
+    /// assert_eq!(Format::detect(b"foo"), None);
+    /// ```
+    pub fn detect(data: &[u8]) -> Option<Format> {
+        #[cfg(feature = "tdb")]
+        if data.starts_with(&crate::tdb::consts::MAGIC) {
+            return Some(Format::Tdb);
+        }
+
+        #[cfg(feature = "lx")]
+        if data.starts_with(b"=@ynm") {
+            return Some(Format::Lx);
+        }
+
+        #[cfg(feature = "xcsoar")]
+        if is_xcsoar_version_line(data) {
+            return Some(Format::XCSoar);
+        }
+
+        #[cfg(not(any(feature = "lx", feature = "tdb", feature = "xcsoar")))]
+        let _ = data;
+
+        None
+    }
+
+    /// Decodes a FlarmNet file in this format.
+    ///
+    /// # Examples
+    ///
+    #[cfg_attr(feature = "tdb", doc = "```")]
+    #[cfg_attr(not(feature = "tdb"), doc = "```ignore")]
+    /// # use flarmnet::Format;
+    /// let file = include_bytes!("../tests/fixtures/flarmnet.tdb");
+    ///
+    /// let result = Format::Tdb.decode(file).unwrap();
+    /// assert_eq!(result.version, 28592);
+    /// assert_eq!(result.records.len(), 3);
+    /// ```
+    #[cfg_attr(
+        not(any(feature = "lx", feature = "tdb", feature = "xcsoar")),
+        allow(unused_variables)
+    )]
+    pub fn decode(self, data: &[u8]) -> Result<DecodedFile, Error> {
+        match self {
+            #[cfg(feature = "lx")]
+            Format::Lx => crate::lx::Lx::decode(data),
+            #[cfg(feature = "tdb")]
+            Format::Tdb => crate::tdb::Tdb::decode(data),
+            #[cfg(feature = "xcsoar")]
+            Format::XCSoar => crate::xcsoar::XCSoar::decode(data),
+        }
+    }
+
+    /// Decodes a FlarmNet file in this format with the given
+    /// [DecodeOptions].
+    ///
+    /// # Examples
+    ///
+    #[cfg_attr(feature = "tdb", doc = "```")]
+    #[cfg_attr(not(feature = "tdb"), doc = "```ignore")]
+    /// # use flarmnet::{DecodeOptions, Format};
+    /// let mut file = include_bytes!("../tests/fixtures/flarmnet.tdb").to_vec();
+    /// file.truncate(file.len() - 10);
+    ///
+    /// assert!(Format::Tdb.decode(&file).is_err());
+    ///
+    /// let result = Format::Tdb.decode_with_options(&file, &DecodeOptions::LENIENT).unwrap();
+    /// assert_eq!(result.records.iter().filter(|it| it.is_ok()).count(), 2);
+    /// ```
+    #[cfg_attr(
+        not(any(feature = "lx", feature = "tdb", feature = "xcsoar")),
+        allow(unused_variables)
+    )]
+    pub fn decode_with_options(
+        self,
+        data: &[u8],
+        options: &DecodeOptions,
+    ) -> Result<DecodedFile, Error> {
+        match self {
+            #[cfg(feature = "lx")]
+            Format::Lx => crate::lx::Lx::decode_with_options(data, options),
+            #[cfg(feature = "tdb")]
+            Format::Tdb => crate::tdb::Tdb::decode_with_options(data, options),
+            #[cfg(feature = "xcsoar")]
+            Format::XCSoar => crate::xcsoar::XCSoar::decode_with_options(data, options),
+        }
+    }
+
+    /// Encodes a FlarmNet file in this format.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use flarmnet::{File, Format};
+    /// let file = File {
+    ///     version: 123,
+    ///     records: vec![],
+    ///     extensions: Default::default(),
+    /// };
+    ///
+    /// for format in Format::ALL {
+    ///     let encoded = format.encode(&file).unwrap();
+    ///     assert_eq!(format.decode(&encoded).unwrap().version, 123);
+    /// }
+    /// ```
+    #[cfg_attr(
+        not(any(feature = "lx", feature = "tdb", feature = "xcsoar")),
+        allow(unused_variables)
+    )]
+    pub fn encode(self, file: &File) -> Result<Vec<u8>, Error> {
+        match self {
+            #[cfg(feature = "lx")]
+            Format::Lx => crate::lx::Lx::encode(file),
+            #[cfg(feature = "tdb")]
+            Format::Tdb => crate::tdb::Tdb::encode(file),
+            #[cfg(feature = "xcsoar")]
+            Format::XCSoar => crate::xcsoar::XCSoar::encode(file),
+        }
+    }
+
+    /// Encodes a FlarmNet file in this format and reports the fields that
+    /// have been truncated because they exceed the limits of the format.
+    ///
+    /// # Examples
+    ///
+    #[cfg_attr(all(feature = "tdb", feature = "xcsoar"), doc = "```")]
+    #[cfg_attr(not(all(feature = "tdb", feature = "xcsoar")), doc = "```ignore")]
+    /// # use flarmnet::{Field, Format};
+    /// let content = std::fs::read("tests/fixtures/data.fln").unwrap();
+    /// let mut file = flarmnet::decode_any(&content).unwrap().into_file();
+    /// file.records[0].registration = "D-KABCDE".to_string();
+    ///
+    /// let (_, report) = Format::XCSoar.encode_with_report(&file).unwrap();
+    /// assert_eq!(report.truncated[0].field, Field::Registration);
+    /// assert_eq!(report.truncated[0].written, "D-KABCD");
+    ///
+    /// let (_, report) = Format::Tdb.encode_with_report(&file).unwrap();
+    /// assert!(report.is_empty());
+    /// ```
+    #[cfg_attr(
+        not(any(feature = "lx", feature = "tdb", feature = "xcsoar")),
+        allow(unused_variables)
+    )]
+    pub fn encode_with_report(self, file: &File) -> Result<(Vec<u8>, EncodeReport), Error> {
+        match self {
+            #[cfg(feature = "lx")]
+            Format::Lx => crate::lx::Lx::encode_with_report(file),
+            #[cfg(feature = "tdb")]
+            Format::Tdb => crate::tdb::Tdb::encode_with_report(file),
+            #[cfg(feature = "xcsoar")]
+            Format::XCSoar => crate::xcsoar::XCSoar::encode_with_report(file),
+        }
+    }
+}
+
+#[cfg(feature = "xcsoar")]
+fn is_xcsoar_version_line(data: &[u8]) -> bool {
+    let line = data.split(|&b| b == b'\n').next().unwrap_or_default();
+    let line = line.strip_suffix(b"\r").unwrap_or(line);
+    line.len() == 6 && line.iter().all(u8::is_ascii_hexdigit)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Format;
+
+    #[test]
+    fn detection_works() {
+        #[cfg(feature = "xcsoar")]
+        assert_eq!(
+            Format::detect(include_bytes!("../tests/fixtures/data.fln")),
+            Some(Format::XCSoar)
+        );
+        #[cfg(feature = "lx")]
+        assert_eq!(
+            Format::detect(include_bytes!("../tests/fixtures/lx.fln")),
+            Some(Format::Lx)
+        );
+        #[cfg(feature = "tdb")]
+        assert_eq!(
+            Format::detect(include_bytes!("../tests/fixtures/flarmnet.tdb")),
+            Some(Format::Tdb)
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "xcsoar")]
+    fn detection_accepts_crlf_version_line() {
+        assert_eq!(Format::detect(b"006fb0\r\n"), Some(Format::XCSoar));
+    }
+
+    #[test]
+    fn detection_fails_for_unknown_content() {
+        assert_eq!(Format::detect(b""), None);
+        assert_eq!(Format::detect(b"<?xml"), None);
+        assert_eq!(Format::detect(b"006fb"), None);
+        assert_eq!(Format::detect(b"006fbx\n"), None);
+        assert_eq!(Format::detect(b"006fb00\n"), None);
+    }
+}
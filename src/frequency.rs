@@ -0,0 +1,244 @@
+use std::fmt;
+use std::str::FromStr;
+use thiserror::Error;
+
+/// Radio frequency in the VHF air band.
+///
+/// The frequency is stored as the channel name in kHz (e.g. `123500` for
+/// `123.500`). Besides the 25 kHz channels this includes 8.33 kHz channel
+/// names (e.g. `122.805` for 122.800 MHz with 8.33 kHz spacing), which means
+/// that the last two digits of the kHz value have to be one of `00`, `05`,
+/// `10`, `15`, `25`, `30`, … `90`.
+///
+/// # Examples
+///
+/// ```
+/// # use flarmnet::Frequency;
+/// let frequency: Frequency = "123.5".parse().unwrap();
+/// assert_eq!(frequency.khz(), 123500);
+/// assert_eq!(frequency.to_string(), "123.500");
+///
+/// let frequency: Frequency = "122,805".parse().unwrap();
+/// assert!(frequency.is_8_33_channel());
+///
+/// assert!("108.000".parse::<Frequency>().is_err());
+/// assert!("123.520".parse::<Frequency>().is_err());
+/// ```
+#[derive(Eq, PartialEq, Ord, PartialOrd, Hash, Clone, Copy)]
+pub struct Frequency(u32);
+
+#[derive(Error, Debug, Eq, PartialEq, Clone)]
+pub enum ParseFrequencyError {
+    #[error("invalid frequency: {0}")]
+    Invalid(String),
+    #[error("frequency outside of the air band: {0}")]
+    OutOfBand(String),
+    #[error("frequency is not a valid 25 kHz or 8.33 kHz channel: {0}")]
+    InvalidChannel(String),
+}
+
+impl Frequency {
+    /// The lowest frequency of the VHF air band (118.000 MHz).
+    pub const MIN: Frequency = Frequency(118_000);
+    /// The highest frequency of the VHF air band (136.975 MHz).
+    pub const MAX: Frequency = Frequency(136_975);
+
+    /// Creates a frequency from a channel name in kHz.
+    ///
+    /// Fails if the value is outside of the air band or is not a valid
+    /// channel name.
+    pub fn from_khz(khz: u32) -> Result<Frequency, ParseFrequencyError> {
+        if !(Self::MIN.0..=Self::MAX.0).contains(&khz) {
+            return Err(ParseFrequencyError::OutOfBand(format_khz(khz)));
+        }
+
+        if khz % 25 == 20 || !khz.is_multiple_of(5) {
+            return Err(ParseFrequencyError::InvalidChannel(format_khz(khz)));
+        }
+
+        Ok(Frequency(khz))
+    }
+
+    /// Returns the channel name in kHz.
+    pub const fn khz(self) -> u32 {
+        self.0
+    }
+
+    /// Returns `true` if this is a channel name that is only available with
+    /// 8.33 kHz channel spacing.
+    pub const fn is_8_33_channel(self) -> bool {
+        !self.0.is_multiple_of(25)
+    }
+}
+
+impl FromStr for Frequency {
+    type Err = ParseFrequencyError;
+
+    /// Parses a frequency in MHz, using either `.` or `,` as decimal
+    /// separator (e.g. `123.5`, `123.500` or `123,500`).
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || ParseFrequencyError::Invalid(s.to_string());
+
+        let (mhz, fraction) = s.split_once(['.', ',']).unwrap_or((s, ""));
+        if mhz.is_empty() || !mhz.bytes().all(|b| b.is_ascii_digit()) {
+            return Err(invalid());
+        }
+        if !fraction.bytes().all(|b| b.is_ascii_digit()) {
+            return Err(invalid());
+        }
+
+        // digits beyond kHz precision are only allowed if they are zero
+        let (fraction, rest) = fraction.split_at(fraction.len().min(3));
+        if rest.bytes().any(|b| b != b'0') {
+            return Err(invalid());
+        }
+
+        let mhz: u32 = mhz.parse().map_err(|_| invalid())?;
+        let khz = format!("{fraction:0<3}")
+            .parse::<u32>()
+            .map_err(|_| invalid())?;
+        let khz = mhz
+            .checked_mul(1000)
+            .and_then(|it| it.checked_add(khz))
+            .ok_or_else(invalid)?;
+
+        Frequency::from_khz(khz)
+    }
+}
+
+fn format_khz(khz: u32) -> String {
+    format!("{}.{:03}", khz / 1000, khz % 1000)
+}
+
+impl fmt::Display for Frequency {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&format_khz(self.0))
+    }
+}
+
+impl fmt::Debug for Frequency {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("Frequency")
+            .field(&format_args!("{self}"))
+            .finish()
+    }
+}
+
+#[cfg(feature = "serde")]
+impl serde::Serialize for Frequency {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for Frequency {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let s = <std::borrow::Cow<'de, str>>::deserialize(deserializer)?;
+        s.parse().map_err(serde::de::Error::custom)
+    }
+}
+
+#[cfg(feature = "miette")]
+impl miette::Diagnostic for ParseFrequencyError {
+    fn code<'a>(&'a self) -> Option<Box<dyn fmt::Display + 'a>> {
+        Some(Box::new("flarmnet::invalid_frequency"))
+    }
+
+    fn help<'a>(&'a self) -> Option<Box<dyn fmt::Display + 'a>> {
+        Some(Box::new(match self {
+            ParseFrequencyError::Invalid(_) => "frequencies are given in MHz, e.g. `123.500`",
+            ParseFrequencyError::OutOfBand(_) => {
+                "the VHF air band ranges from 118.000 MHz to 136.975 MHz"
+            }
+            ParseFrequencyError::InvalidChannel(_) => {
+                "the last two digits must name a 25 kHz or 8.33 kHz channel, e.g. `122.805`"
+            }
+        }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Frequency;
+    use insta::assert_debug_snapshot;
+
+    fn parse(s: &str) -> u32 {
+        s.parse::<Frequency>().unwrap().khz()
+    }
+
+    #[test]
+    fn parsing_works() {
+        assert_eq!(parse("123.5"), 123500);
+        assert_eq!(parse("123.50"), 123500);
+        assert_eq!(parse("123.500"), 123500);
+        assert_eq!(parse("123.5000"), 123500);
+        assert_eq!(parse("123,500"), 123500);
+        assert_eq!(parse("123"), 123000);
+        assert_eq!(parse("118.000"), 118000);
+        assert_eq!(parse("136.975"), 136975);
+    }
+
+    #[test]
+    fn parsing_works_for_8_33_channels() {
+        for channel in ["122.805", "122.810", "122.815", "122.830", "122.890"] {
+            let frequency: Frequency = channel.parse().unwrap();
+            assert!(frequency.is_8_33_channel(), "{channel}");
+        }
+
+        for channel in ["122.800", "122.825", "122.850", "122.875"] {
+            let frequency: Frequency = channel.parse().unwrap();
+            assert!(!frequency.is_8_33_channel(), "{channel}");
+        }
+    }
+
+    #[test]
+    fn parsing_fails_for_invalid_values() {
+        assert_debug_snapshot!("".parse::<Frequency>().unwrap_err(), @r###"
+        Invalid(
+            "",
+        )
+        "###);
+        assert_debug_snapshot!("abc".parse::<Frequency>().unwrap_err(), @r###"
+        Invalid(
+            "abc",
+        )
+        "###);
+        assert!(".500".parse::<Frequency>().is_err());
+        assert!("123.5.0".parse::<Frequency>().is_err());
+        assert!("123.5001".parse::<Frequency>().is_err());
+        assert!("-123.5".parse::<Frequency>().is_err());
+        assert!(" 123.5".parse::<Frequency>().is_err());
+        assert!("99999999.5".parse::<Frequency>().is_err());
+    }
+
+    #[test]
+    fn parsing_fails_outside_of_air_band() {
+        assert_debug_snapshot!("117.975".parse::<Frequency>().unwrap_err(), @r###"
+        OutOfBand(
+            "117.975",
+        )
+        "###);
+        assert!("136.980".parse::<Frequency>().is_err());
+        assert!("1234.5".parse::<Frequency>().is_err());
+    }
+
+    #[test]
+    fn parsing_fails_for_invalid_channels() {
+        assert_debug_snapshot!("123.520".parse::<Frequency>().unwrap_err(), @r###"
+        InvalidChannel(
+            "123.520",
+        )
+        "###);
+        assert!("123.501".parse::<Frequency>().is_err());
+        assert!("123.545".parse::<Frequency>().is_err());
+        assert!("123.595".parse::<Frequency>().is_err());
+    }
+
+    #[test]
+    fn formatting_works() {
+        let frequency = Frequency::from_khz(123050).unwrap();
+        assert_eq!(frequency.to_string(), "123.050");
+        assert_eq!(format!("{frequency:?}"), "Frequency(123.050)");
+    }
+}
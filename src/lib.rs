@@ -1,23 +1,71 @@
+#[cfg(feature = "csv")]
+pub mod csv;
 #[cfg(feature = "lx")]
 pub mod lx;
+#[cfg(feature = "ogn")]
+pub mod ogn;
 #[cfg(feature = "tdb")]
 pub mod tdb;
 #[cfg(feature = "xcsoar")]
 pub mod xcsoar;
 
+mod codec;
+mod db;
+mod decode_options;
+mod diff;
+mod error;
+mod field;
+mod flarm_id;
+mod format;
+mod frequency;
+mod merge;
+mod report;
+#[cfg(test)]
+mod test_util;
+
+pub use codec::*;
+pub use db::{Duplicate, FlarmNetDb};
+pub use decode_options::DecodeOptions;
+pub use diff::{ChangedRecord, Diff, DiffOptions, FieldChange, diff, diff_with_options};
+pub use error::{Error, Operation};
+pub use field::Field;
+pub use flarm_id::{FlarmId, ParseFlarmIdError};
+pub use format::Format;
+pub use frequency::{Frequency, ParseFrequencyError};
+pub use merge::{
+    Conflict, ConflictValue, MergeOptions, MergePolicy, MergeResult, MergeSource, merge,
+};
+pub use report::{EncodeReport, TruncatedField};
+
+use std::collections::BTreeMap;
+
+/// Format-specific data without a dedicated [Record] or [File] field.
+///
+/// Decoders store data here that would otherwise be lost (e.g. unknown XML
+/// elements), so that encoders can write it back. Keys are prefixed with the
+/// name of the format module (e.g. `tdb.reserved`) and are documented in the
+/// respective module. Encoders ignore keys of other formats.
+pub type Extensions = BTreeMap<String, String>;
+
 #[derive(Debug, Eq, PartialEq, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Record {
-    pub flarm_id: String,
+    pub flarm_id: FlarmId,
     pub pilot_name: String,
     pub airfield: String,
     pub plane_type: String,
     pub registration: String,
     pub call_sign: String,
-    pub frequency: String,
+    pub frequency: Option<Frequency>,
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub extensions: Extensions,
 }
 
 #[derive(Debug, Eq, PartialEq, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct File {
     pub version: u32,
     pub records: Vec<Record>,
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub extensions: Extensions,
 }
@@ -0,0 +1,202 @@
+use super::cipher;
+use super::decode::{RecordDecoder, Root};
+use super::encode::{write_footer, write_header, write_record};
+use super::{DecodeError, DecodedFile, EncodeError};
+use crate::{DecodeOptions, Extensions, File, Record};
+use tokio::io::{AsyncRead, AsyncWrite, AsyncWriteExt, BufReader};
+
+type XmlReader<R> = quick_xml::Reader<BufReader<cipher::AsyncReader<R>>>;
+
+/// Decodes a FlarmNet file in LX format from any [AsyncRead] implementation.
+///
+/// The records are decoded one at a time, see [AsyncReader].
+///
+/// # Examples
+///
+/// ```
+/// # #[tokio::main(flavor = "current_thread")]
+/// # async fn main() {
+/// let file = tokio::fs::File::open("tests/fixtures/lx.fln").await.unwrap();
+/// let result = flarmnet::lx::decode_async_reader(file).await.unwrap();
+/// assert_eq!(result.version, 28592);
+/// # }
+/// ```
+pub async fn decode_async_reader<R: AsyncRead + Unpin>(
+    reader: R,
+) -> Result<DecodedFile, DecodeError> {
+    decode_async_reader_with_options(reader, &DecodeOptions::default()).await
+}
+
+/// Decodes a FlarmNet file in LX format from any [AsyncRead] implementation
+/// with the given [DecodeOptions].
+pub async fn decode_async_reader_with_options<R: AsyncRead + Unpin>(
+    reader: R,
+    options: &DecodeOptions,
+) -> Result<DecodedFile, DecodeError> {
+    let mut reader = AsyncReader::with_options(reader, options).await?;
+
+    // unlike the reader, errors outside of the records fail the whole file
+    let mut records = Vec::new();
+    while let Some(result) = reader.read_next().await? {
+        records.push(result);
+    }
+
+    Ok(DecodedFile {
+        version: reader.version,
+        records,
+        extensions: reader.extensions,
+    })
+}
+
+/// Async version of [Reader](super::Reader) for [AsyncRead]
+/// implementations.
+///
+/// The records are read and decoded one at a time by [AsyncReader::next].
+///
+/// # Examples
+///
+/// ```
+/// # #[tokio::main(flavor = "current_thread")]
+/// # async fn main() {
+/// let file = tokio::fs::File::open("tests/fixtures/lx.fln").await.unwrap();
+/// let mut reader = flarmnet::lx::AsyncReader::new(file).await.unwrap();
+/// assert_eq!(reader.version(), 28592);
+///
+/// while let Some(result) = reader.next().await {
+///     let record = result.unwrap();
+///     println!("{}: {}", record.flarm_id, record.registration);
+/// }
+/// # }
+/// ```
+pub struct AsyncReader<R: AsyncRead + Unpin> {
+    xml: XmlReader<R>,
+    buf: Vec<u8>,
+    version: u32,
+    extensions: Extensions,
+    records: RecordDecoder,
+}
+
+impl<R: AsyncRead + Unpin> AsyncReader<R> {
+    /// Creates a new reader and reads the file version from the `FLARMNET`
+    /// root element.
+    pub async fn new(inner: R) -> Result<Self, DecodeError> {
+        Self::with_options(inner, &DecodeOptions::default()).await
+    }
+
+    /// Creates a new reader with the given [DecodeOptions], see
+    /// [Reader::with_options](super::Reader::with_options).
+    pub async fn with_options(inner: R, options: &DecodeOptions) -> Result<Self, DecodeError> {
+        let reader = BufReader::new(cipher::AsyncReader::new(inner));
+        let mut xml = quick_xml::Reader::from_reader(reader);
+        xml.check_end_names(false);
+        let mut buf = Vec::new();
+
+        let root = loop {
+            buf.clear();
+            let event = xml.read_event_into_async(&mut buf).await?;
+            if let Some(root) = Root::decode(xml.decoder(), event, options)? {
+                break root;
+            }
+        };
+
+        Ok(Self {
+            xml,
+            buf,
+            version: root.version,
+            extensions: root.extensions,
+            records: RecordDecoder::new(options, root.empty),
+        })
+    }
+
+    /// Returns the file version.
+    pub fn version(&self) -> u32 {
+        self.version
+    }
+
+    /// Returns the file-level [Extensions], see [module documentation](super).
+    pub fn extensions(&self) -> &Extensions {
+        &self.extensions
+    }
+
+    /// Reads and decodes the next `FLARMDATA` element, or returns `None` at
+    /// the end of the file.
+    ///
+    /// Like [Reader](super::Reader), this continues after records that can
+    /// not be decoded, and returns `None` after errors outside of the
+    /// records.
+    pub async fn next(&mut self) -> Option<Result<Record, DecodeError>> {
+        self.read_next()
+            .await
+            .unwrap_or_else(|error| Some(Err(error)))
+    }
+
+    pub fn into_inner(self) -> R {
+        self.xml.into_inner().into_inner().into_inner()
+    }
+
+    /// See [Reader::read_next](super::Reader).
+    async fn read_next(&mut self) -> Result<Option<Result<Record, DecodeError>>, DecodeError> {
+        loop {
+            if let Some(result) = self.records.queued.take() {
+                return result.map(Some);
+            }
+            if self.records.done {
+                return Ok(None);
+            }
+
+            let position = self.xml.buffer_position();
+            self.buf.clear();
+            let event = self.xml.read_event_into_async(&mut self.buf).await;
+            let span = position..self.xml.buffer_position();
+            if let Some(result) = self.records.handle(self.xml.decoder(), event, span) {
+                return result.map(Some);
+            }
+        }
+    }
+}
+
+/// Async version of [Writer](super::Writer) for [AsyncWrite] implementations.
+///
+/// The records are encoded one at a time, and the inner writer is flushed
+/// after the whole file has been written.
+pub struct AsyncWriter<W: AsyncWrite + Unpin> {
+    writer: cipher::AsyncWriter<W>,
+}
+
+impl<W: AsyncWrite + Unpin> AsyncWriter<W> {
+    pub fn new(inner: W) -> Self {
+        Self {
+            writer: cipher::AsyncWriter::new(inner),
+        }
+    }
+
+    pub async fn write(&mut self, file: &File) -> Result<(), EncodeError> {
+        let mut xml = quick_xml::Writer::new(Vec::new());
+
+        write_header(&mut xml, file)?;
+        self.write_buffer(&mut xml).await?;
+        for record in &file.records {
+            write_record(&mut xml, record)?;
+            self.write_buffer(&mut xml).await?;
+        }
+        write_footer(&mut xml)?;
+        self.write_buffer(&mut xml).await?;
+        self.writer.flush().await?;
+
+        Ok(())
+    }
+
+    pub fn into_inner(self) -> W {
+        self.writer.into_inner()
+    }
+
+    /// Writes the encoded XML to the inner writer and clears the buffer.
+    async fn write_buffer(
+        &mut self,
+        xml: &mut quick_xml::Writer<Vec<u8>>,
+    ) -> Result<(), EncodeError> {
+        self.writer.write_all(xml.get_ref()).await?;
+        xml.get_mut().clear();
+        Ok(())
+    }
+}
@@ -1,4 +1,8 @@
 use std::io::{Read, Write};
+#[cfg(feature = "async")]
+use std::pin::Pin;
+#[cfg(feature = "async")]
+use std::task::{Context, Poll};
 
 #[derive(Clone)]
 pub struct Reader<R: Read> {
@@ -9,6 +13,10 @@ impl<R: Read> Reader<R> {
     pub fn new(inner: R) -> Self {
         Self { inner }
     }
+
+    pub fn into_inner(self) -> R {
+        self.inner
+    }
 }
 
 impl<R: Read> Read for Reader<R> {
@@ -47,6 +55,79 @@ impl<W: Write> Write for Writer<W> {
     }
 }
 
+/// Async version of [Reader] for [tokio::io::AsyncRead] implementations.
+#[cfg(feature = "async")]
+pub struct AsyncReader<R: tokio::io::AsyncRead + Unpin> {
+    inner: R,
+}
+
+#[cfg(feature = "async")]
+impl<R: tokio::io::AsyncRead + Unpin> AsyncReader<R> {
+    pub fn new(inner: R) -> Self {
+        Self { inner }
+    }
+
+    pub fn into_inner(self) -> R {
+        self.inner
+    }
+}
+
+#[cfg(feature = "async")]
+impl<R: tokio::io::AsyncRead + Unpin> tokio::io::AsyncRead for AsyncReader<R> {
+    fn poll_read(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut tokio::io::ReadBuf<'_>,
+    ) -> Poll<std::io::Result<()>> {
+        let start = buf.filled().len();
+        let result = Pin::new(&mut self.inner).poll_read(cx, buf);
+        if let Poll::Ready(Ok(())) = result {
+            for byte in &mut buf.filled_mut()[start..] {
+                *byte = byte.wrapping_sub(1);
+            }
+        }
+        result
+    }
+}
+
+/// Async version of [Writer] for [tokio::io::AsyncWrite] implementations.
+#[cfg(feature = "async")]
+pub struct AsyncWriter<W: tokio::io::AsyncWrite + Unpin> {
+    inner: W,
+}
+
+#[cfg(feature = "async")]
+impl<W: tokio::io::AsyncWrite + Unpin> AsyncWriter<W> {
+    pub fn new(inner: W) -> Self {
+        Self { inner }
+    }
+
+    pub fn into_inner(self) -> W {
+        self.inner
+    }
+}
+
+#[cfg(feature = "async")]
+impl<W: tokio::io::AsyncWrite + Unpin> tokio::io::AsyncWrite for AsyncWriter<W> {
+    fn poll_write(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &[u8],
+    ) -> Poll<std::io::Result<usize>> {
+        // each byte is encrypted independently, so partial writes are fine
+        let new_buf: Vec<_> = buf.iter().map(|b| b.wrapping_add(1)).collect();
+        Pin::new(&mut self.inner).poll_write(cx, &new_buf)
+    }
+
+    fn poll_flush(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<std::io::Result<()>> {
+        Pin::new(&mut self.inner).poll_flush(cx)
+    }
+
+    fn poll_shutdown(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<std::io::Result<()>> {
+        Pin::new(&mut self.inner).poll_shutdown(cx)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::{Reader, Writer};
@@ -81,4 +162,23 @@ mod tests {
             b"=@ynm!wfstjpo>#2/1#!fodpejoh>#VUG.9#@?=GMBSNOFU!Wfstjpo>#11:44f#?=GMBSNEBUB!GmbsnJE>#111111#?"
         );
     }
+
+    #[cfg(feature = "async")]
+    #[tokio::test]
+    async fn async_cipher_round_trips() {
+        use super::{AsyncReader, AsyncWriter};
+        use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+        let plain = b"<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n<FLARMNET Version=\"00933e\">\n";
+
+        let mut writer = AsyncWriter::new(Vec::new());
+        writer.write_all(plain).await.unwrap();
+        let encrypted = writer.into_inner();
+        assert_eq!(encrypted, encrypt(plain));
+
+        let mut reader = AsyncReader::new(&encrypted[..]);
+        let mut decrypted = Vec::new();
+        reader.read_to_end(&mut decrypted).await.unwrap();
+        assert_eq!(decrypted, plain);
+    }
 }
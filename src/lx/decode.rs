@@ -1,15 +1,24 @@
-use crate::Record;
+use super::{ATTRIBUTE_PREFIX, ELEMENT_PREFIX, FREQUENCY_KEY};
 use crate::lx::cipher;
-use minidom::{Element, NSChoice, quick_xml};
-use std::io::BufReader;
+use crate::{DecodeOptions, Extensions, Frequency, ParseFrequencyError, Record};
+use quick_xml::encoding::Decoder;
+use quick_xml::events::{BytesEnd, BytesStart, Event};
+use std::io::{BufReader, Read};
+use std::ops::Range;
 use thiserror::Error;
 
 #[derive(Error, Debug)]
 pub enum DecodeError {
     #[error(transparent)]
-    Xml(#[from] minidom::Error),
+    Io(#[from] std::io::Error),
+    #[error(transparent)]
+    XmlReader(#[from] quick_xml::Error),
+    #[error("unexpected end of file")]
+    UnexpectedEof,
     #[error("missing XML element: {0}")]
     MissingElement(String),
+    #[error("unclosed XML element: {0}")]
+    UnclosedElement(String),
     #[error("missing file version")]
     MissingVersion,
     #[error("invalid file version: {0}")]
@@ -18,12 +27,27 @@ pub enum DecodeError {
     MissingFlarmId,
     #[error("invalid FLARM id: {0}")]
     InvalidFlarmId(String),
+    #[error(transparent)]
+    InvalidFrequency(#[from] ParseFrequencyError),
+    /// A `FLARMDATA` element could not be decoded. `index` is the zero-based
+    /// index of the record, `position` the byte offset of the element and
+    /// `span` the byte range of the invalid part of the element (e.g. the
+    /// `FREQUENCY` child element) in the file, which are the same for the
+    /// encrypted and the decrypted XML.
+    #[error("invalid FLARMDATA element at byte offset {position}")]
+    Record {
+        index: usize,
+        position: usize,
+        span: Range<usize>,
+        source: Box<DecodeError>,
+    },
 }
 
 #[derive(Debug)]
 pub struct DecodedFile {
     pub version: u32,
     pub records: Vec<Result<Record, DecodeError>>,
+    pub extensions: Extensions,
 }
 
 /// Decodes a FlarmNet file in LX format.
@@ -58,28 +82,66 @@ pub struct DecodedFile {
 /// assert_eq!(result.records.iter().filter(|it| it.is_ok()).count(), 3);
 /// ```
 pub fn decode_file(file: &[u8]) -> Result<DecodedFile, DecodeError> {
-    let reader = cipher::Reader::new(file);
+    decode_reader(file)
+}
 
-    let mut reader = quick_xml::Reader::from_reader(BufReader::new(reader));
-    let root: Element = Element::from_reader(&mut reader)?;
-    if root.name() != "FLARMNET" {
-        return Err(DecodeError::MissingElement("FLARMNET".to_string()));
-    }
+/// Decodes a FlarmNet file in LX format with the given [DecodeOptions].
+pub fn decode_file_with_options(
+    file: &[u8],
+    options: &DecodeOptions,
+) -> Result<DecodedFile, DecodeError> {
+    decode_reader_with_options(file, options)
+}
 
-    let version = root.attr("Version").ok_or(DecodeError::MissingVersion)?;
-    let version = u32::from_str_radix(version, 16)
-        .map_err(|_| DecodeError::InvalidVersion(version.to_string()))?;
+/// Decodes a FlarmNet file in LX format from any [Read] implementation.
+///
+/// # Examples
+///
+/// ```
+/// let file = std::fs::File::open("tests/fixtures/lx.fln").unwrap();
+/// let result = flarmnet::lx::decode_reader(file).unwrap();
+/// assert_eq!(result.version, 28592);
+/// assert_eq!(result.records.len(), 3);
+/// ```
+pub fn decode_reader<R: Read>(reader: R) -> Result<DecodedFile, DecodeError> {
+    decode_reader_with_options(reader, &DecodeOptions::default())
+}
 
-    let records = root
-        .children()
-        .filter(|child| child.name() == "FLARMDATA")
-        .map(convert)
-        .collect();
+/// Decodes a FlarmNet file in LX format from any [Read] implementation with
+/// the given [DecodeOptions].
+pub fn decode_reader_with_options<R: Read>(
+    reader: R,
+    options: &DecodeOptions,
+) -> Result<DecodedFile, DecodeError> {
+    let mut reader = Reader::with_options(reader, options)?;
+    let version = reader.version();
+    let extensions = reader.extensions().clone();
 
-    Ok(DecodedFile { version, records })
+    // unlike the iterator, errors outside of the records fail the whole file
+    let mut records = Vec::new();
+    while let Some(result) = reader.read_next()? {
+        records.push(result);
+    }
+
+    Ok(DecodedFile {
+        version,
+        records,
+        extensions,
+    })
 }
 
-/// Converts a `minidom::Element` to a `flarmnet::Record`.
+/// Decodes the value of the `Version` attribute of the `FLARMNET` element.
+fn decode_version(version: Option<&str>, options: &DecodeOptions) -> Result<u32, DecodeError> {
+    match version {
+        Some(version) => u32::from_str_radix(version, 16)
+            .map_err(|_| DecodeError::InvalidVersion(version.to_string())),
+        None if options.accept_missing_version => Ok(0),
+        None => Err(DecodeError::MissingVersion),
+    }
+}
+
+/// Builds a `flarmnet::Record` from the attributes and the (name, text)
+/// pairs of the child elements of a `FLARMDATA` element.
 ///
 /// Expected structure:
 ///
@@ -93,45 +155,77 @@ pub fn decode_file(file: &[u8]) -> Result<DecodedFile, DecodeError> {
 ///   <FREQUENCY></FREQUENCY>
 /// </FLARMDATA>
 /// ```
-fn convert(element: &Element) -> Result<Record, DecodeError> {
-    let flarm_id = element
-        .attr("FlarmID")
-        .ok_or(DecodeError::MissingFlarmId)?
-        .to_string();
-
-    if u32::from_str_radix(&flarm_id, 16).is_err() {
-        return Err(DecodeError::InvalidFlarmId(flarm_id));
-    }
-
-    let pilot_name = element
-        .get_child("NAME", NSChoice::Any)
-        .map(|e| e.text())
-        .unwrap_or_default();
-
-    let airfield = element
-        .get_child("AIRFIELD", NSChoice::Any)
-        .map(|e| e.text())
-        .unwrap_or_default();
-
-    let plane_type = element
-        .get_child("TYPE", NSChoice::Any)
-        .map(|e| e.text())
-        .unwrap_or_default();
-
-    let registration = element
-        .get_child("REG", NSChoice::Any)
-        .map(|e| e.text())
-        .unwrap_or_default();
-
-    let call_sign = element
-        .get_child("COMPID", NSChoice::Any)
-        .map(|e| e.text())
-        .unwrap_or_default();
-
-    let frequency = element
-        .get_child("FREQUENCY", NSChoice::Any)
-        .map(|e| e.text())
-        .unwrap_or_default();
+///
+/// Unknown attributes and child elements are stored in the record
+/// extensions. If a child element appears multiple times, the first one is
+/// used.
+fn build_record<'a>(
+    attributes: impl IntoIterator<Item = (&'a str, &'a str)>,
+    children: impl IntoIterator<Item = (&'a str, String)>,
+    options: &DecodeOptions,
+) -> Result<Record, DecodeError> {
+    let mut flarm_id = None;
+    let mut extensions = Extensions::new();
+    for (name, value) in attributes {
+        if name == "FlarmID" {
+            flarm_id = Some(value);
+        } else {
+            let key = format!("{ATTRIBUTE_PREFIX}{name}");
+            extensions.insert(key, value.to_string());
+        }
+    }
+
+    let flarm_id = flarm_id.ok_or(DecodeError::MissingFlarmId)?;
+    if !options.accept_non_canonical_ids && !is_canonical_id(flarm_id) {
+        return Err(DecodeError::InvalidFlarmId(flarm_id.to_string()));
+    }
+    let flarm_id = flarm_id
+        .parse()
+        .map_err(|_| DecodeError::InvalidFlarmId(flarm_id.to_string()))?;
+
+    let mut fields: [Option<String>; 6] = Default::default();
+    for (name, text) in children {
+        match FIELD_ELEMENTS.iter().position(|it| *it == name) {
+            Some(i) => {
+                fields[i].get_or_insert(text);
+            }
+            None => {
+                let key = format!("{ELEMENT_PREFIX}{name}");
+                extensions.entry(key).or_insert(text);
+            }
+        }
+    }
+
+    if !options.accept_missing_elements
+        && let Some(i) = fields.iter().position(Option::is_none)
+    {
+        return Err(DecodeError::MissingElement(FIELD_ELEMENTS[i].to_string()));
+    }
+
+    let [
+        pilot_name,
+        airfield,
+        plane_type,
+        registration,
+        call_sign,
+        frequency,
+    ] = fields.map(|field| {
+        let field = field.unwrap_or_default();
+        match options.trim_whitespace {
+            true => field.trim().to_string(),
+            false => field,
+        }
+    });
+
+    let frequency = match frequency.parse::<Frequency>() {
+        _ if frequency.is_empty() => None,
+        Ok(frequency) => Some(frequency),
+        Err(_) if options.accept_invalid_frequencies => {
+            extensions.insert(FREQUENCY_KEY.to_string(), frequency);
+            None
+        }
+        Err(error) => return Err(error.into()),
+    };
 
     Ok(Record {
         flarm_id,
@@ -141,15 +235,592 @@ fn convert(element: &Element) -> Result<Record, DecodeError> {
         registration,
         call_sign,
         frequency,
+        extensions,
     })
 }
 
+const FIELD_ELEMENTS: [&str; 6] = ["NAME", "AIRFIELD", "TYPE", "REG", "COMPID", "FREQUENCY"];
+
+/// Checks whether the FLARM ID consists of six uppercase hex digits.
+fn is_canonical_id(flarm_id: &str) -> bool {
+    flarm_id.len() == 6
+        && flarm_id
+            .bytes()
+            .all(|b| b.is_ascii_digit() || (b'A'..=b'F').contains(&b))
+}
+
+type XmlReader<R> = quick_xml::Reader<BufReader<cipher::Reader<R>>>;
+
+/// Streaming decoder for FlarmNet files in LX format.
+///
+/// The records are decoded one at a time based on XML events. The file
+/// version is read when the reader is created.
+///
+/// Records that can not be decoded are reported as [DecodeError::Record]
+/// and the reader continues with the next `FLARMDATA` element. This includes
+/// XML errors within a `FLARMDATA` element (e.g. an unescaped `&` in a pilot
+/// name), after which the rest of the broken element is skipped. Only errors
+/// outside of the records (e.g. a truncated file) end the iteration.
+///
+/// # Examples
+///
+/// ```
+/// let file = std::fs::File::open("tests/fixtures/lx.fln").unwrap();
+/// let reader = flarmnet::lx::Reader::new(file).unwrap();
+/// assert_eq!(reader.version(), 28592);
+///
+/// for result in reader {
+///     let record = result.unwrap();
+///     println!("{}: {}", record.flarm_id, record.registration);
+/// }
+/// ```
+pub struct Reader<R: Read> {
+    xml: XmlReader<R>,
+    buf: Vec<u8>,
+    version: u32,
+    extensions: Extensions,
+    records: RecordDecoder,
+}
+
+impl<R: Read> Reader<R> {
+    /// Creates a new reader and reads the file version from the `FLARMNET`
+    /// root element.
+    pub fn new(inner: R) -> Result<Self, DecodeError> {
+        Self::with_options(inner, &DecodeOptions::default())
+    }
+
+    /// Creates a new reader with the given [DecodeOptions] and reads the file
+    /// version from the `FLARMNET` root element.
+    pub fn with_options(inner: R, options: &DecodeOptions) -> Result<Self, DecodeError> {
+        let reader = BufReader::new(cipher::Reader::new(inner));
+        let mut xml = quick_xml::Reader::from_reader(reader);
+        // end tags are checked within the records instead, so that the reader
+        // can recover from broken `FLARMDATA` elements
+        xml.check_end_names(false);
+        let mut buf = Vec::new();
+
+        let root = loop {
+            buf.clear();
+            let event = xml.read_event_into(&mut buf)?;
+            if let Some(root) = Root::decode(xml.decoder(), event, options)? {
+                break root;
+            }
+        };
+
+        Ok(Self {
+            xml,
+            buf,
+            version: root.version,
+            extensions: root.extensions,
+            records: RecordDecoder::new(options, root.empty),
+        })
+    }
+
+    /// Returns the file version.
+    pub fn version(&self) -> u32 {
+        self.version
+    }
+
+    /// Returns the file-level [Extensions], see [module documentation](super).
+    pub fn extensions(&self) -> &Extensions {
+        &self.extensions
+    }
+
+    pub fn into_inner(self) -> R {
+        self.xml.into_inner().into_inner().into_inner()
+    }
+
+    /// Reads the next `FLARMDATA` element, skipping any other elements.
+    ///
+    /// Errors of the record itself are returned as `Ok(Some(Err(_)))`, while
+    /// errors that prevent reading any further records are returned as
+    /// `Err(_)`. Both are wrapped in [DecodeError::Record] if they occur
+    /// within a `FLARMDATA` element.
+    fn read_next(&mut self) -> Result<Option<Result<Record, DecodeError>>, DecodeError> {
+        loop {
+            if let Some(result) = self.records.queued.take() {
+                return result.map(Some);
+            }
+            if self.records.done {
+                return Ok(None);
+            }
+
+            let position = self.xml.buffer_position();
+            self.buf.clear();
+            let event = self.xml.read_event_into(&mut self.buf);
+            let span = position..self.xml.buffer_position();
+            if let Some(result) = self.records.handle(self.xml.decoder(), event, span) {
+                return result.map(Some);
+            }
+        }
+    }
+}
+
+impl<R: Read> Iterator for Reader<R> {
+    type Item = Result<Record, DecodeError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.read_next().unwrap_or_else(|error| Some(Err(error)))
+    }
+}
+
+/// Decoded `FLARMNET` root element.
+pub(super) struct Root {
+    pub(super) version: u32,
+    pub(super) extensions: Extensions,
+    /// Whether the root element is an empty element without any records.
+    pub(super) empty: bool,
+}
+
+impl Root {
+    /// Decodes the root element from its start event, or returns `None` for
+    /// the events in front of it (e.g. the XML declaration).
+    pub(super) fn decode(
+        decoder: Decoder,
+        event: Event<'_>,
+        options: &DecodeOptions,
+    ) -> Result<Option<Self>, DecodeError> {
+        let (start, empty) = match event {
+            Event::Start(start) => (start, false),
+            Event::Empty(start) => (start, true),
+            Event::Eof => return Err(DecodeError::MissingElement("FLARMNET".to_string())),
+            // XML declaration, comments, whitespace, ...
+            _ => return Ok(None),
+        };
+        if start.name().as_ref() != b"FLARMNET" {
+            return Err(DecodeError::MissingElement("FLARMNET".to_string()));
+        }
+
+        let mut version = None;
+        let mut extensions = Extensions::new();
+        for (name, value) in read_attributes(decoder, &start)? {
+            if name == "Version" {
+                version = Some(value);
+            } else {
+                extensions.insert(format!("{ATTRIBUTE_PREFIX}{name}"), value);
+            }
+        }
+
+        Ok(Some(Self {
+            version: decode_version(version.as_deref(), options)?,
+            extensions,
+            empty,
+        }))
+    }
+}
+
+/// Result of a `FLARMDATA` element, see [Reader::read_next].
+type RecordResult = Result<Result<Record, DecodeError>, DecodeError>;
+
+/// Decodes the `FLARMDATA` elements from the XML events following the root
+/// element.
+///
+/// The events are passed in by [Reader] and [AsyncReader](super::AsyncReader),
+/// so that both read and decode the records one event at a time.
+pub(super) struct RecordDecoder {
+    options: DecodeOptions,
+    state: State,
+    /// Number of `FLARMDATA` elements that have been read so far.
+    index: usize,
+    /// Result of a `FLARMDATA` element that has been read while handling the
+    /// previous event, which has to be returned before the next event.
+    pub(super) queued: Option<RecordResult>,
+    /// Whether the end of the root element or an error that prevents reading
+    /// any further records has been reached.
+    pub(super) done: bool,
+}
+
+enum State {
+    /// Outside of the `FLARMDATA` elements. Other elements are skipped up to
+    /// their end tag, tracked by name and nesting depth.
+    Outside { skipped: Option<(Vec<u8>, usize)> },
+    /// Within a `FLARMDATA` element.
+    Record(PartialRecord),
+    /// Within a broken `FLARMDATA` element, whose remaining content is
+    /// skipped.
+    ///
+    /// Skipping stops at the `</FLARMDATA>` end tag, or before the next
+    /// `FLARMDATA` element if the end tag is missing. Errors at the end of
+    /// the file are left to be reported outside of the element.
+    Broken {
+        index: usize,
+        position: usize,
+        error: DecodeError,
+    },
+}
+
+struct PartialRecord {
+    index: usize,
+    /// Byte range of the start tag.
+    start: Range<usize>,
+    attributes: Vec<(String, String)>,
+    /// Name, text content and byte range of the child elements.
+    children: Vec<(String, String, Range<usize>)>,
+    /// Child element whose text content is being read. The content of nested
+    /// elements is ignored.
+    child: Option<Child>,
+}
+
+struct Child {
+    name: String,
+    text: String,
+    depth: usize,
+    /// Byte offset of the start tag.
+    position: usize,
+}
+
+impl RecordDecoder {
+    pub(super) fn new(options: &DecodeOptions, done: bool) -> Self {
+        Self {
+            options: *options,
+            state: State::Outside { skipped: None },
+            index: 0,
+            queued: None,
+            done,
+        }
+    }
+
+    /// Handles the next XML event, which has been read from the byte range
+    /// `span`, and returns the result of a `FLARMDATA` element once it ends.
+    pub(super) fn handle(
+        &mut self,
+        decoder: Decoder,
+        event: Result<Event<'_>, quick_xml::Error>,
+        span: Range<usize>,
+    ) -> Option<RecordResult> {
+        let state = std::mem::replace(&mut self.state, State::Outside { skipped: None });
+        match state {
+            State::Outside { skipped } => self.handle_outside(decoder, event, span, skipped),
+            State::Record(record) => self.handle_record(decoder, event, span, record),
+            State::Broken {
+                index,
+                position,
+                error,
+            } => match event {
+                Ok(event) if is_record_start(&event) => {
+                    let error = record_error(index, position..span.start, error);
+                    Some(self.end_before(decoder, event, span, error))
+                }
+                Ok(Event::End(end)) if end.name().as_ref() == b"FLARMDATA" => {
+                    Some(Ok(Err(record_error(index, position..span.end, error))))
+                }
+                Ok(Event::End(end)) if end.name().as_ref() == b"FLARMNET" => {
+                    self.done = true;
+                    Some(Ok(Err(record_error(index, position..span.start, error))))
+                }
+                // the event is read again and reported outside of the element
+                Ok(Event::Eof) => Some(Ok(Err(record_error(index, position..span.start, error)))),
+                Err(_) if span.is_empty() => {
+                    Some(Ok(Err(record_error(index, position..span.start, error))))
+                }
+                _ => {
+                    self.state = State::Broken {
+                        index,
+                        position,
+                        error,
+                    };
+                    None
+                }
+            },
+        }
+    }
+
+    fn handle_outside(
+        &mut self,
+        decoder: Decoder,
+        event: Result<Event<'_>, quick_xml::Error>,
+        span: Range<usize>,
+        skipped: Option<(Vec<u8>, usize)>,
+    ) -> Option<RecordResult> {
+        let event = match event {
+            Ok(event) => event,
+            Err(error) => return Some(self.fail(error.into())),
+        };
+
+        if let Some((name, depth)) = skipped {
+            let skipped = match event {
+                Event::Start(start) if start.name().as_ref() == name => Some((name, depth + 1)),
+                Event::End(end) if end.name().as_ref() == name => {
+                    depth.checked_sub(1).map(|depth| (name, depth))
+                }
+                Event::Eof => return Some(self.fail(DecodeError::UnexpectedEof)),
+                _ => Some((name, depth)),
+            };
+            self.state = State::Outside { skipped };
+            return None;
+        }
+
+        match event {
+            Event::Start(start) if start.name().as_ref() == b"FLARMDATA" => {
+                self.start_record(decoder, &start, false, span)
+            }
+            Event::Empty(start) if start.name().as_ref() == b"FLARMDATA" => {
+                self.start_record(decoder, &start, true, span)
+            }
+            Event::Start(start) => {
+                let skipped = Some((start.name().as_ref().to_vec(), 0));
+                self.state = State::Outside { skipped };
+                None
+            }
+            Event::End(end) if end.name().as_ref() == b"FLARMNET" => {
+                self.done = true;
+                None
+            }
+            Event::Eof => Some(self.fail(DecodeError::UnexpectedEof)),
+            // stray end tags, text, comments, ...
+            _ => None,
+        }
+    }
+
+    fn start_record(
+        &mut self,
+        decoder: Decoder,
+        start: &BytesStart<'_>,
+        empty: bool,
+        span: Range<usize>,
+    ) -> Option<RecordResult> {
+        let index = self.index;
+        self.index += 1;
+
+        let attributes = match read_attributes(decoder, start) {
+            Ok(attributes) => attributes,
+            Err(error) if empty => return Some(Ok(Err(record_error(index, span, error)))),
+            Err(error) => {
+                let position = span.start;
+                self.state = State::Broken {
+                    index,
+                    position,
+                    error,
+                };
+                return None;
+            }
+        };
+
+        if empty {
+            let attributes = attributes.iter().map(|(k, v)| (k.as_str(), v.as_str()));
+            let result = build_record(attributes, [], &self.options);
+            return Some(Ok(result.map_err(|error| record_error(index, span, error))));
+        }
+
+        self.state = State::Record(PartialRecord {
+            index,
+            start: span,
+            attributes,
+            children: Vec::new(),
+            child: None,
+        });
+        None
+    }
+
+    fn handle_record(
+        &mut self,
+        decoder: Decoder,
+        event: Result<Event<'_>, quick_xml::Error>,
+        span: Range<usize>,
+        mut record: PartialRecord,
+    ) -> Option<RecordResult> {
+        let event = match event {
+            // the file ends within the element
+            Ok(Event::Eof) => {
+                let span = record.start.start..span.end;
+                let error = record_error(record.index, span, DecodeError::UnexpectedEof);
+                return Some(self.fail(error));
+            }
+            Ok(event) if is_record_start(&event) => {
+                let name = match record.child {
+                    Some(child) => child.name,
+                    None => "FLARMDATA".to_string(),
+                };
+                let error = DecodeError::UnclosedElement(name);
+                let error = record_error(record.index, record.start.start..span.start, error);
+                return Some(self.end_before(decoder, event, span, error));
+            }
+            Ok(event) => event,
+            Err(error) => return self.break_record(record, error.into()),
+        };
+
+        let result = match &mut record.child {
+            Some(child) => match read_text(decoder, child, event) {
+                Ok(true) => {
+                    let child = record.child.take().expect("child element exists");
+                    let span = child.position..span.end;
+                    record.children.push((child.name, child.text, span));
+                    Ok(false)
+                }
+                result => result,
+            },
+            None => read_child(decoder, &mut record, event, span.clone()),
+        };
+        match result {
+            Ok(false) => {
+                self.state = State::Record(record);
+                None
+            }
+            Ok(true) => {
+                let attributes = record.attributes.iter();
+                let attributes = attributes.map(|(k, v)| (k.as_str(), v.as_str()));
+                let children = record.children.iter();
+                let children = children.map(|(k, v, _)| (k.as_str(), v.clone()));
+                let result = build_record(attributes, children, &self.options);
+                let span = record.start.start..span.end;
+                Some(Ok(result.map_err(|error| {
+                    let error_span = error_span(&record, &error).unwrap_or(span.clone());
+                    DecodeError::Record {
+                        index: record.index,
+                        position: span.start,
+                        span: error_span,
+                        source: Box::new(error),
+                    }
+                })))
+            }
+            Err(error) => self.break_record(record, error),
+        }
+    }
+
+    /// Continues with skipping the rest of a broken `FLARMDATA` element.
+    fn break_record(&mut self, record: PartialRecord, error: DecodeError) -> Option<RecordResult> {
+        self.state = State::Broken {
+            index: record.index,
+            position: record.start.start,
+            error,
+        };
+        None
+    }
+
+    /// Ends a `FLARMDATA` element that is missing its end tag with the given
+    /// error, and handles the start of the next element, whose result is
+    /// queued if it is an empty element.
+    fn end_before(
+        &mut self,
+        decoder: Decoder,
+        event: Event<'_>,
+        span: Range<usize>,
+        error: DecodeError,
+    ) -> RecordResult {
+        self.queued = self.handle_outside(decoder, Ok(event), span, None);
+        Ok(Err(error))
+    }
+
+    /// Stops reading any further records because of the given error.
+    fn fail(&mut self, error: DecodeError) -> RecordResult {
+        self.done = true;
+        Err(error)
+    }
+}
+
+/// Handles an event between the child elements of a `FLARMDATA` element and
+/// returns whether the element has ended.
+fn read_child(
+    decoder: Decoder,
+    record: &mut PartialRecord,
+    event: Event<'_>,
+    span: Range<usize>,
+) -> Result<bool, DecodeError> {
+    match event {
+        Event::Start(start) => {
+            let name = decode_name(decoder, &start)?;
+            let text = String::new();
+            record.child = Some(Child {
+                name,
+                text,
+                depth: 0,
+                position: span.start,
+            });
+        }
+        Event::Empty(start) => {
+            let name = decode_name(decoder, &start)?;
+            record.children.push((name, String::new(), span));
+        }
+        Event::End(end) if end.name().as_ref() == b"FLARMDATA" => return Ok(true),
+        Event::End(end) => return Err(end_mismatch("FLARMDATA", &end)),
+        _ => {}
+    }
+    Ok(false)
+}
+
+/// Handles an event within a child element of a `FLARMDATA` element and
+/// returns whether the child element has ended.
+fn read_text(decoder: Decoder, child: &mut Child, event: Event<'_>) -> Result<bool, DecodeError> {
+    match event {
+        Event::Text(content) if child.depth == 0 => child.text.push_str(&content.unescape()?),
+        Event::CData(content) if child.depth == 0 => {
+            child.text.push_str(&decoder.decode(&content)?);
+        }
+        Event::Start(_) => child.depth += 1,
+        Event::End(end) if child.depth == 0 && end.name().as_ref() == child.name.as_bytes() => {
+            return Ok(true);
+        }
+        Event::End(end) if child.depth == 0 => return Err(end_mismatch(&child.name, &end)),
+        Event::End(_) => child.depth -= 1,
+        _ => {}
+    }
+    Ok(false)
+}
+
+fn decode_name(decoder: Decoder, start: &BytesStart<'_>) -> Result<String, DecodeError> {
+    Ok(decoder.decode(start.name().as_ref())?.into_owned())
+}
+
+/// Checks whether the event starts a `FLARMDATA` element.
+fn is_record_start(event: &Event<'_>) -> bool {
+    match event {
+        Event::Start(start) | Event::Empty(start) => start.name().as_ref() == b"FLARMDATA",
+        _ => false,
+    }
+}
+
+/// Returns the byte range of the part of a `FLARMDATA` element that caused
+/// the error of [build_record], or `None` if it refers to the whole element.
+fn error_span(record: &PartialRecord, error: &DecodeError) -> Option<Range<usize>> {
+    match error {
+        DecodeError::MissingFlarmId | DecodeError::InvalidFlarmId(_) => Some(record.start.clone()),
+        // the first `FREQUENCY` element is the one that has been decoded
+        DecodeError::InvalidFrequency(_) => record
+            .children
+            .iter()
+            .find(|(name, _, _)| name == "FREQUENCY")
+            .map(|(_, _, span)| span.clone()),
+        _ => None,
+    }
+}
+
+fn record_error(index: usize, span: Range<usize>, error: DecodeError) -> DecodeError {
+    DecodeError::Record {
+        index,
+        position: span.start,
+        span,
+        source: Box::new(error),
+    }
+}
+
+fn end_mismatch(expected: &str, end: &BytesEnd<'_>) -> DecodeError {
+    let expected = expected.to_string();
+    let found = String::from_utf8_lossy(end.name().as_ref()).into_owned();
+    quick_xml::Error::EndEventMismatch { expected, found }.into()
+}
+
+fn read_attributes(
+    decoder: Decoder,
+    start: &BytesStart<'_>,
+) -> Result<Vec<(String, String)>, DecodeError> {
+    start
+        .attributes()
+        .map(|attribute| {
+            let attribute = attribute.map_err(quick_xml::Error::from)?;
+            let name = decoder.decode(attribute.key.as_ref())?.into_owned();
+            let value = attribute.unescape_value()?.into_owned();
+            Ok((name, value))
+        })
+        .collect()
+}
+
 #[cfg(test)]
 mod tests {
+    use crate::DecodeOptions;
+    use crate::Record;
     use crate::lx::cipher::Writer;
-    use crate::lx::decode::{convert, decode_file};
+    use crate::lx::decode::{DecodeError, Reader, decode_file, decode_file_with_options};
     use insta::assert_debug_snapshot;
-    use minidom::Element;
     use std::io::copy;
 
     fn encrypt(mut s: &[u8]) -> Vec<u8> {
@@ -159,12 +830,23 @@ mod tests {
         writer.into_inner()
     }
 
+    /// Decodes a single `FLARMDATA` element, unwrapping [DecodeError::Record].
+    fn decode_element(element: &str, options: &DecodeOptions) -> Result<Record, DecodeError> {
+        let file = format!(r#"<FLARMNET Version="000001">{element}</FLARMNET>"#);
+        let file = encrypt(file.as_bytes());
+        let mut reader = Reader::with_options(&file[..], options).unwrap();
+        match reader.next().unwrap() {
+            Err(DecodeError::Record { source, .. }) => Err(*source),
+            result => result,
+        }
+    }
+
     #[test]
     fn decoding_fails_for_empty_file() {
         let file = b"";
         assert_debug_snapshot!(decode_file(file).unwrap_err(), @r###"
-        Xml(
-            EndOfDocument,
+        MissingElement(
+            "FLARMNET",
         )
         "###);
     }
@@ -173,8 +855,8 @@ mod tests {
     fn decoding_fails_for_invalid_file() {
         let file = b"foo";
         assert_debug_snapshot!(decode_file(file).unwrap_err(), @r###"
-        Xml(
-            EndOfDocument,
+        MissingElement(
+            "FLARMNET",
         )
         "###);
     }
@@ -219,7 +901,7 @@ mod tests {
 
     #[test]
     fn converting_works() {
-        let element: Element = r#"
+        let element = r#"
             <FLARMDATA FlarmID="c0ffee">
               <NAME>John Doe</NAME>
               <AIRFIELD>EDKA</AIRFIELD>
@@ -228,26 +910,31 @@ mod tests {
               <COMPID>AS</COMPID>
               <FREQUENCY>123.500</FREQUENCY>
             </FLARMDATA>
-        "#
-        .parse()
-        .unwrap();
+        "#;
 
-        assert_debug_snapshot!(convert(&element).unwrap(), @r###"
+        assert_debug_snapshot!(decode_element(element, &DecodeOptions::default()).unwrap(), @r###"
         Record {
-            flarm_id: "c0ffee",
+            flarm_id: FlarmId(
+                C0FFEE,
+            ),
             pilot_name: "John Doe",
             airfield: "EDKA",
             plane_type: "ASG 29",
             registration: "D-KESH",
             call_sign: "AS",
-            frequency: "123.500",
+            frequency: Some(
+                Frequency(
+                    123.500,
+                ),
+            ),
+            extensions: {},
         }
         "###);
     }
 
     #[test]
     fn converting_fails_for_missing_flarm_id() {
-        let element: Element = r#"
+        let element = r#"
             <FLARMDATA>
               <NAME></NAME>
               <AIRFIELD>000000</AIRFIELD>
@@ -256,16 +943,14 @@ mod tests {
               <COMPID></COMPID>
               <FREQUENCY></FREQUENCY>
             </FLARMDATA>
-        "#
-        .parse()
-        .unwrap();
+        "#;
 
-        assert_debug_snapshot!(convert(&element).unwrap_err(), @"MissingFlarmId");
+        assert_debug_snapshot!(decode_element(element, &DecodeOptions::default()).unwrap_err(), @"MissingFlarmId");
     }
 
     #[test]
     fn converting_fails_for_invalid_flarm_id() {
-        let element: Element = r#"
+        let element = r#"
             <FLARMDATA FlarmID="foo">
               <NAME></NAME>
               <AIRFIELD>000000</AIRFIELD>
@@ -274,14 +959,222 @@ mod tests {
               <COMPID></COMPID>
               <FREQUENCY></FREQUENCY>
             </FLARMDATA>
-        "#
-        .parse()
-        .unwrap();
+        "#;
 
-        assert_debug_snapshot!(convert(&element).unwrap_err(), @r###"
+        assert_debug_snapshot!(decode_element(element, &DecodeOptions::default()).unwrap_err(), @r###"
         InvalidFlarmId(
             "foo",
         )
         "###);
     }
+
+    #[test]
+    fn converting_keeps_unknown_content() {
+        let element = r#"
+            <FLARMDATA FlarmID="000001" Foo="bar">
+              <NAME></NAME>
+              <TYPE>Paraglider</TYPE>
+              <PHONE>+49 123 456</PHONE>
+            </FLARMDATA>
+        "#;
+
+        assert_debug_snapshot!(decode_element(element, &DecodeOptions::default()).unwrap().extensions, @r###"
+        {
+            "lx.attr.Foo": "bar",
+            "lx.element.PHONE": "+49 123 456",
+        }
+        "###);
+    }
+
+    #[test]
+    fn reader_fails_for_missing_root_element() {
+        let file = encrypt(br#"<?xml version="1.0" encoding="UTF-8"?><FOO></FOO>"#);
+        assert_debug_snapshot!(Reader::new(&file[..]).err().unwrap(), @r###"
+        MissingElement(
+            "FLARMNET",
+        )
+        "###);
+    }
+
+    #[test]
+    fn reader_skips_invalid_records_and_fails_on_truncated_file() {
+        let file = encrypt(
+            br#"<?xml version="1.0" encoding="UTF-8"?>
+                <FLARMNET Version="006fb0">
+                <FLARMDATA FlarmID="XYZ"></FLARMDATA>
+                <FOO><BAR/></FOO>
+                <FLARMDATA FlarmID="3EE3C7"><NAME>Tobias</NAME><REG/></FLARMDATA>
+                <FLARMDATA FlarmID="3EE3C8">"#,
+        );
+        let reader = Reader::new(&file[..]).unwrap();
+        let results = reader.map(|it| it.map(|record| record.pilot_name));
+        assert_debug_snapshot!(results.collect::<Vec<_>>(), @r###"
+        [
+            Err(
+                Record {
+                    index: 0,
+                    position: 99,
+                    span: 99..124,
+                    source: InvalidFlarmId(
+                        "XYZ",
+                    ),
+                },
+            ),
+            Ok(
+                "Tobias",
+            ),
+            Err(
+                Record {
+                    index: 2,
+                    position: 269,
+                    span: 269..297,
+                    source: UnexpectedEof,
+                },
+            ),
+        ]
+        "###);
+    }
+
+    #[test]
+    fn decoding_recovers_from_broken_records() {
+        let file = encrypt(
+            br#"<?xml version="1.0" encoding="UTF-8"?>
+                <FLARMNET Version="006fb0">
+                <FLARMDATA FlarmID="000001"><NAME>Smith & Sons</NAME></FLARMDATA>
+                <FLARMDATA FlarmID="000002"><NAME>John</REG></FLARMDATA>
+                <FLARMDATA FlarmID="000003"><NAME>Jane</NAME>
+                <FLARMDATA FlarmID="000004"><NAME>Tobias</NAME></FLARMDATA>
+                </FLARMNET>"#,
+        );
+
+        let result = decode_file(&file).unwrap();
+        let results = result.records.into_iter();
+        let results = results.map(|it| it.map(|record| record.pilot_name));
+        assert_debug_snapshot!(results.collect::<Vec<_>>(), @r###"
+        [
+            Err(
+                Record {
+                    index: 0,
+                    position: 99,
+                    span: 99..164,
+                    source: XmlReader(
+                        EscapeError(
+                            UnterminatedEntity(
+                                6..12,
+                            ),
+                        ),
+                    ),
+                },
+            ),
+            Err(
+                Record {
+                    index: 1,
+                    position: 181,
+                    span: 181..237,
+                    source: XmlReader(
+                        EndEventMismatch {
+                            expected: "NAME",
+                            found: "REG",
+                        },
+                    ),
+                },
+            ),
+            Err(
+                Record {
+                    index: 2,
+                    position: 254,
+                    span: 254..316,
+                    source: UnclosedElement(
+                        "FLARMDATA",
+                    ),
+                },
+            ),
+            Ok(
+                "Tobias",
+            ),
+        ]
+        "###);
+    }
+
+    #[test]
+    fn decoding_keeps_records_with_invalid_frequency() {
+        let element = r#"<FLARMDATA FlarmID="DD1234"><REG>D-0816</REG><FREQUENCY>129.970</FREQUENCY></FLARMDATA>"#;
+
+        let record = decode_element(element, &DecodeOptions::LENIENT).unwrap();
+        assert_eq!(record.registration, "D-0816");
+        assert_eq!(record.frequency, None);
+        assert_eq!(record.extensions["lx.frequency"], "129.970");
+
+        let options = DecodeOptions {
+            accept_invalid_frequencies: false,
+            ..DecodeOptions::LENIENT
+        };
+        assert_debug_snapshot!(decode_element(element, &options).unwrap_err(), @r###"
+        InvalidFrequency(
+            InvalidChannel(
+                "129.970",
+            ),
+        )
+        "###);
+    }
+
+    #[test]
+    fn strict_decoding_rejects_non_canonical_records() {
+        let element = r#"
+            <FLARMDATA FlarmID="dd1234">
+              <NAME> John Doe </NAME>
+              <TYPE>Paraglider</TYPE>
+            </FLARMDATA>
+        "#;
+
+        let record = decode_element(element, &DecodeOptions::default()).unwrap();
+        assert_eq!(record.pilot_name, " John Doe ");
+        assert_eq!(record.registration, "");
+
+        let record = decode_element(element, &DecodeOptions::LENIENT).unwrap();
+        assert_eq!(record.pilot_name, "John Doe");
+
+        let options = DecodeOptions::STRICT;
+        assert_debug_snapshot!(decode_element(element, &options).unwrap_err(), @r###"
+        InvalidFlarmId(
+            "dd1234",
+        )
+        "###);
+
+        let options = DecodeOptions {
+            accept_non_canonical_ids: true,
+            ..DecodeOptions::STRICT
+        };
+        assert_debug_snapshot!(decode_element(element, &options).unwrap_err(), @r###"
+        MissingElement(
+            "AIRFIELD",
+        )
+        "###);
+
+        let options = DecodeOptions {
+            accept_missing_elements: true,
+            ..options
+        };
+        assert_eq!(
+            decode_element(element, &options).unwrap().pilot_name,
+            " John Doe "
+        );
+    }
+
+    #[test]
+    fn lenient_decoding_accepts_missing_version() {
+        let file = encrypt(
+            br#"<?xml version="1.0" encoding="UTF-8"?>
+                <FLARMNET>
+                </FLARMNET>"#,
+        );
+
+        assert_debug_snapshot!(decode_file(&file).unwrap_err(), @"MissingVersion");
+
+        let result = decode_file_with_options(&file, &DecodeOptions::LENIENT).unwrap();
+        assert_eq!(result.version, 0);
+
+        let reader = Reader::with_options(&file[..], &DecodeOptions::LENIENT).unwrap();
+        assert_eq!(reader.version(), 0);
+    }
 }
@@ -0,0 +1,68 @@
+use super::DecodeError;
+use miette::{Diagnostic, LabeledSpan};
+use std::fmt::Display;
+
+/// The labels refer to the decrypted XML, so the output of the
+/// [cipher::Reader](super::cipher::Reader) has to be attached via
+/// [miette::Report::with_source_code] to render them.
+impl Diagnostic for DecodeError {
+    fn code<'a>(&'a self) -> Option<Box<dyn Display + 'a>> {
+        let code = match self {
+            DecodeError::Io(_) => return None,
+            DecodeError::XmlReader(_) => "flarmnet::lx::invalid_xml",
+            DecodeError::UnexpectedEof => "flarmnet::lx::unexpected_eof",
+            DecodeError::MissingElement(_) => "flarmnet::lx::missing_element",
+            DecodeError::UnclosedElement(_) => "flarmnet::lx::unclosed_element",
+            DecodeError::MissingVersion => "flarmnet::lx::missing_version",
+            DecodeError::InvalidVersion(_) => "flarmnet::lx::invalid_version",
+            DecodeError::MissingFlarmId => "flarmnet::lx::missing_flarm_id",
+            DecodeError::InvalidFlarmId(_) => "flarmnet::lx::invalid_flarm_id",
+            DecodeError::InvalidFrequency(error) => return error.code(),
+            DecodeError::Record { source, .. } => return source.code(),
+        };
+        Some(Box::new(code))
+    }
+
+    fn help<'a>(&'a self) -> Option<Box<dyn Display + 'a>> {
+        let help = match self {
+            DecodeError::MissingElement(name) if name == "FLARMNET" => {
+                "the file needs a `FLARMNET` root element"
+            }
+            DecodeError::MissingElement(_) => {
+                "records contain `NAME`, `AIRFIELD`, `TYPE`, `REG`, `COMPID` and `FREQUENCY` elements"
+            }
+            DecodeError::UnclosedElement(_) => {
+                "each `FLARMDATA` element has to be closed before the next one starts"
+            }
+            DecodeError::MissingVersion | DecodeError::InvalidVersion(_) => {
+                "the `Version` attribute of `FLARMNET` contains the file version as hex number, e.g. `006FB0`"
+            }
+            DecodeError::MissingFlarmId | DecodeError::InvalidFlarmId(_) => {
+                "the `FlarmID` attribute contains six hex digits, e.g. `3EE3C7`"
+            }
+            DecodeError::InvalidFrequency(error) => return error.help(),
+            DecodeError::Record { source, .. } => return source.help(),
+            _ => return None,
+        };
+        Some(Box::new(help))
+    }
+
+    fn labels(&self) -> Option<Box<dyn Iterator<Item = LabeledSpan> + '_>> {
+        let DecodeError::Record { span, source, .. } = self else {
+            return None;
+        };
+
+        let label = match &**source {
+            DecodeError::XmlReader(_) => "invalid XML".to_string(),
+            DecodeError::UnexpectedEof => "element is not closed".to_string(),
+            DecodeError::UnclosedElement(name) => format!("`{name}` element is not closed"),
+            DecodeError::MissingElement(name) => format!("missing `{name}` element"),
+            DecodeError::MissingFlarmId => "missing `FlarmID` attribute".to_string(),
+            DecodeError::InvalidFlarmId(_) => "FLARM ID is not hex".to_string(),
+            DecodeError::InvalidFrequency(_) => "invalid frequency".to_string(),
+            _ => "invalid record".to_string(),
+        };
+        let label = LabeledSpan::new_with_span(Some(label), span.clone());
+        Some(Box::new(std::iter::once(label)))
+    }
+}
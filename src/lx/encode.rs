@@ -1,12 +1,16 @@
-use crate::File;
+use super::{ATTRIBUTE_PREFIX, ELEMENT_PREFIX, FREQUENCY_KEY};
 use crate::lx::cipher;
+use crate::{Extensions, File, Record};
 use quick_xml;
+use quick_xml::escape::partial_escape;
 use quick_xml::events::{BytesDecl, BytesEnd, BytesStart, BytesText, Event};
 use std::io::{Cursor, Write};
 use thiserror::Error;
 
 #[derive(Error, Debug)]
 pub enum EncodeError {
+    #[error(transparent)]
+    Io(#[from] std::io::Error),
     #[error(transparent)]
     Xml(#[from] quick_xml::Error),
 }
@@ -21,15 +25,17 @@ pub enum EncodeError {
 ///     version: 123,
 ///     records: vec![
 ///         Record {
-///             flarm_id: "3EE3C7".to_string(),
+///             flarm_id: "3EE3C7".parse().unwrap(),
 ///             pilot_name: "Tobias Bieniek".to_string(),
 ///             airfield: "EDKA".to_string(),
 ///             plane_type: "LS6a".to_string(),
 ///             registration: "D-0816".to_string(),
 ///             call_sign: "SG".to_string(),
-///             frequency: "130.530".to_string(),
+///             frequency: Some("130.530".parse().unwrap()),
+///             extensions: Default::default(),
 ///         }
-///     ]
+///     ],
+///     extensions: Default::default(),
 /// };
 ///
 /// let result = flarmnet::lx::encode_file(&file);
@@ -60,67 +66,178 @@ impl<W: Write> Writer<W> {
     pub fn write(&mut self, file: &File) -> Result<(), EncodeError> {
         let writer = &mut self.xml_writer;
 
-        writer.write_event(Event::Decl(BytesDecl::new("1.0", Some("UTF-8"), None)))?;
-        writer.write_event(Event::Text(BytesText::from_escaped("\n")))?;
-
-        let version = format!("{:06x?}", file.version);
-        writer.write_event(Event::Start(
-            BytesStart::new("FLARMNET")
-                .with_attributes(vec![("Version".as_bytes(), version.as_bytes())]),
-        ))?;
-        writer.write_event(Event::Text(BytesText::from_escaped("\n")))?;
-
+        write_header(writer, file)?;
         for record in &file.records {
-            writer
-                .write_event(Event::Start(BytesStart::new("FLARMDATA").with_attributes(
-                    vec![("FlarmID".as_bytes(), record.flarm_id.as_bytes())],
-                )))?;
-
-            if !record.pilot_name.is_empty() {
-                writer.write_event(Event::Start(BytesStart::new("NAME")))?;
-                writer.write_event(Event::Text(BytesText::new(&record.pilot_name)))?;
-                writer.write_event(Event::End(BytesEnd::new("NAME")))?;
-            }
-
-            if !record.airfield.is_empty() {
-                writer.write_event(Event::Start(BytesStart::new("AIRFIELD")))?;
-                writer.write_event(Event::Text(BytesText::new(&record.airfield)))?;
-                writer.write_event(Event::End(BytesEnd::new("AIRFIELD")))?;
-            }
-
-            if !record.plane_type.is_empty() {
-                writer.write_event(Event::Start(BytesStart::new("TYPE")))?;
-                writer.write_event(Event::Text(BytesText::new(&record.plane_type)))?;
-                writer.write_event(Event::End(BytesEnd::new("TYPE")))?;
-            }
-
-            if !record.registration.is_empty() {
-                writer.write_event(Event::Start(BytesStart::new("REG")))?;
-                writer.write_event(Event::Text(BytesText::new(&record.registration)))?;
-                writer.write_event(Event::End(BytesEnd::new("REG")))?;
-            }
-
-            if !record.call_sign.is_empty() {
-                writer.write_event(Event::Start(BytesStart::new("COMPID")))?;
-                writer.write_event(Event::Text(BytesText::new(&record.call_sign)))?;
-                writer.write_event(Event::End(BytesEnd::new("COMPID")))?;
-            }
-
-            if !record.frequency.is_empty() {
-                writer.write_event(Event::Start(BytesStart::new("FREQUENCY")))?;
-                writer.write_event(Event::Text(BytesText::new(&record.frequency)))?;
-                writer.write_event(Event::End(BytesEnd::new("FREQUENCY")))?;
-            }
-
-            writer.write_event(Event::End(BytesEnd::new("FLARMDATA")))?;
-            writer.write_event(Event::Text(BytesText::from_escaped("\n")))?;
+            write_record(writer, record)?;
         }
-        writer.write_event(Event::End(BytesEnd::new("FLARMNET")))?;
-
-        Ok(())
+        write_footer(writer)
     }
 
     pub fn into_inner(self) -> W {
         self.xml_writer.into_inner().into_inner()
     }
 }
+
+/// Writes the XML declaration and the start tag of the `FLARMNET` element.
+pub(super) fn write_header<W: Write>(
+    writer: &mut quick_xml::Writer<W>,
+    file: &File,
+) -> Result<(), EncodeError> {
+    writer.write_event(Event::Decl(BytesDecl::new("1.0", Some("UTF-8"), None)))?;
+    writer.write_event(Event::Text(BytesText::from_escaped("\n")))?;
+
+    let version = format!("{:06x?}", file.version);
+    let mut root = BytesStart::new("FLARMNET");
+    root.push_attribute(("Version", version.as_str()));
+    push_extension_attributes(&mut root, &file.extensions);
+    writer.write_event(Event::Start(root))?;
+    writer.write_event(Event::Text(BytesText::from_escaped("\n")))?;
+
+    Ok(())
+}
+
+/// Writes the `FLARMDATA` element of a record.
+pub(super) fn write_record<W: Write>(
+    writer: &mut quick_xml::Writer<W>,
+    record: &Record,
+) -> Result<(), EncodeError> {
+    let flarm_id = record.flarm_id.to_string();
+    let mut start = BytesStart::new("FLARMDATA");
+    start.push_attribute(("FlarmID", flarm_id.as_str()));
+    push_extension_attributes(&mut start, &record.extensions);
+    writer.write_event(Event::Start(start))?;
+    writer.write_event(Event::Text(BytesText::from_escaped("\n")))?;
+
+    let frequency = match record.frequency {
+        Some(frequency) => Some(frequency.to_string()),
+        None => record.extensions.get(FREQUENCY_KEY).cloned(),
+    };
+
+    write_element(writer, "NAME", &record.pilot_name)?;
+    write_element(writer, "AIRFIELD", &record.airfield)?;
+    write_element(writer, "TYPE", &record.plane_type)?;
+    write_element(writer, "REG", &record.registration)?;
+    write_element(writer, "COMPID", &record.call_sign)?;
+    write_element(
+        writer,
+        "FREQUENCY",
+        frequency.as_deref().unwrap_or_default(),
+    )?;
+
+    for (key, value) in &record.extensions {
+        if let Some(name) = key.strip_prefix(ELEMENT_PREFIX) {
+            write_element(writer, name, value)?;
+        }
+    }
+
+    writer.write_event(Event::End(BytesEnd::new("FLARMDATA")))?;
+    writer.write_event(Event::Text(BytesText::from_escaped("\n")))?;
+
+    Ok(())
+}
+
+/// Writes the end tag of the `FLARMNET` element.
+pub(super) fn write_footer<W: Write>(writer: &mut quick_xml::Writer<W>) -> Result<(), EncodeError> {
+    writer.write_event(Event::End(BytesEnd::new("FLARMNET")))?;
+    Ok(())
+}
+
+/// Writes an indented `<name>text</name>` line.
+fn write_element<W: Write>(
+    writer: &mut quick_xml::Writer<W>,
+    name: &str,
+    text: &str,
+) -> Result<(), EncodeError> {
+    writer.write_event(Event::Text(BytesText::from_escaped("\t")))?;
+    writer.write_event(Event::Start(BytesStart::new(name)))?;
+    if !text.is_empty() {
+        // only `<`, `>` and `&` need to be escaped in text content
+        writer.write_event(Event::Text(BytesText::from_escaped(partial_escape(text))))?;
+    }
+    writer.write_event(Event::End(BytesEnd::new(name)))?;
+    writer.write_event(Event::Text(BytesText::from_escaped("\n")))?;
+
+    Ok(())
+}
+
+fn push_extension_attributes(start: &mut BytesStart<'_>, extensions: &Extensions) {
+    for (key, value) in extensions {
+        if let Some(name) = key.strip_prefix(ATTRIBUTE_PREFIX) {
+            start.push_attribute((name, value.as_str()));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::encode_file;
+    use crate::File;
+    use crate::lx::cipher::Writer;
+    use crate::lx::decode_file;
+    use std::io::copy;
+
+    fn encrypt(mut s: &[u8]) -> Vec<u8> {
+        let vec = Vec::with_capacity(s.len());
+        let mut writer = Writer::new(vec);
+        copy(&mut s, &mut writer).unwrap();
+        writer.into_inner()
+    }
+
+    #[test]
+    fn encoding_round_trips_unknown_content() {
+        let xml = "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n<FLARMNET Version=\"00007b\" Source=\"club\">\n<FLARMDATA FlarmID=\"3EE3C7\" Status=\"new\">\n\t<NAME>Tom &amp; Jerry</NAME>\n\t<AIRFIELD>EDKA</AIRFIELD>\n\t<TYPE>LS6a</TYPE>\n\t<REG>D-0816</REG>\n\t<COMPID>SG</COMPID>\n\t<FREQUENCY>130.530</FREQUENCY>\n\t<PHONE>+49 123 456</PHONE>\n</FLARMDATA>\n</FLARMNET>";
+        let encrypted = encrypt(xml.as_bytes());
+
+        let decoded = decode_file(&encrypted).unwrap();
+        let file = File {
+            version: decoded.version,
+            records: decoded.records.into_iter().map(Result::unwrap).collect(),
+            extensions: decoded.extensions,
+        };
+        assert_eq!(encode_file(&file).unwrap(), encrypted);
+    }
+
+    #[test]
+    fn encoding_sorts_unknown_content_and_drops_duplicates() {
+        let xml = "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n<FLARMNET Version=\"00007b\">\n<FLARMDATA FlarmID=\"3EE3C7\">\n  <PHONE>1</PHONE>\n  <EMAIL>a@b.c</EMAIL>\n  <PHONE>2</PHONE>\n</FLARMDATA>\n</FLARMNET>";
+        let decoded = decode_file(&encrypt(xml.as_bytes())).unwrap();
+        let file = File {
+            version: decoded.version,
+            records: decoded.records.into_iter().map(Result::unwrap).collect(),
+            extensions: decoded.extensions,
+        };
+
+        let expected = "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n<FLARMNET Version=\"00007b\">\n<FLARMDATA FlarmID=\"3EE3C7\">\n\t<NAME></NAME>\n\t<AIRFIELD></AIRFIELD>\n\t<TYPE></TYPE>\n\t<REG></REG>\n\t<COMPID></COMPID>\n\t<FREQUENCY></FREQUENCY>\n\t<EMAIL>a@b.c</EMAIL>\n\t<PHONE>1</PHONE>\n</FLARMDATA>\n</FLARMNET>";
+        assert_eq!(encode_file(&file).unwrap(), encrypt(expected.as_bytes()));
+    }
+
+    #[test]
+    fn encoding_round_trips_invalid_frequencies() {
+        let xml = "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n<FLARMNET Version=\"00007b\">\n<FLARMDATA FlarmID=\"3EE3C7\">\n\t<NAME></NAME>\n\t<AIRFIELD></AIRFIELD>\n\t<TYPE></TYPE>\n\t<REG>D-0816</REG>\n\t<COMPID></COMPID>\n\t<FREQUENCY>129.970</FREQUENCY>\n</FLARMDATA>\n</FLARMNET>";
+        let encrypted = encrypt(xml.as_bytes());
+
+        let decoded = decode_file(&encrypted).unwrap();
+        let file = File {
+            version: decoded.version,
+            records: decoded.records.into_iter().map(Result::unwrap).collect(),
+            extensions: decoded.extensions,
+        };
+        assert_eq!(file.records[0].frequency, None);
+        assert_eq!(encode_file(&file).unwrap(), encrypted);
+    }
+
+    #[test]
+    fn encoding_round_trips_padded_values() {
+        let xml = "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n<FLARMNET Version=\"00007b\">\n<FLARMDATA FlarmID=\"3EE3C7\">\n\t<NAME>John Doe </NAME>\n\t<AIRFIELD></AIRFIELD>\n\t<TYPE></TYPE>\n\t<REG>D-0816 </REG>\n\t<COMPID> SG</COMPID>\n\t<FREQUENCY></FREQUENCY>\n</FLARMDATA>\n</FLARMNET>";
+        let encrypted = encrypt(xml.as_bytes());
+
+        let decoded = decode_file(&encrypted).unwrap();
+        let file = File {
+            version: decoded.version,
+            records: decoded.records.into_iter().map(Result::unwrap).collect(),
+            extensions: decoded.extensions,
+        };
+        assert_eq!(file.records[0].registration, "D-0816 ");
+        assert_eq!(encode_file(&file).unwrap(), encrypted);
+    }
+}
@@ -18,13 +18,83 @@
 //! </FLARMNET>
 //! ```
 //!
-//! The [decode_file] function can be used to decode FlarmNet files in
-//! LXNav/Naviter file format. The [encode_file] function can be used to write
+//! The [decode_file] and [decode_reader] functions can be used to decode
+//! FlarmNet files in LXNav/Naviter file format, while the [Reader] decodes
+//! the records one at a time. The [encode_file] function can be used to write
 //! such files.
+//!
+//! ## Extensions
+//!
+//! Unknown attributes of the `FLARMNET` and `FLARMDATA` elements are stored
+//! in the [Extensions](crate::Extensions) of the file and records as
+//! `lx.attr.<name>`. Unknown child elements of `FLARMDATA` are stored as
+//! `lx.element.<name>` with their text content. The encoder writes them back
+//! after the known attributes and elements.
+//!
+//! Since the extensions are a map, this is only lossless for files that are
+//! formatted like the encoder output: unknown attributes and elements are
+//! written in alphabetical order of their names and with the same
+//! indentation as the known elements, and only the first of multiple
+//! elements with the same name is kept.
+//!
+//! If the `FREQUENCY` element does not contain a valid
+//! [Frequency](crate::Frequency) (see
+//! [accept_invalid_frequencies](crate::DecodeOptions::accept_invalid_frequencies)),
+//! its text is stored as `lx.frequency`, and the encoder writes it back if
+//! the record has no frequency.
+//!
+//! ## Errors
+//!
+//! Each `FLARMDATA` element is decoded separately, so invalid values or
+//! malformed XML within an element (e.g. an unescaped `&`) are reported as
+//! [DecodeError::Record] for that record, and decoding continues with the
+//! next element.
 
+#[cfg(feature = "async")]
+mod async_io;
 pub mod cipher;
 mod decode;
+#[cfg(feature = "miette")]
+mod diagnostic;
 mod encode;
 
+#[cfg(feature = "async")]
+pub use async_io::*;
 pub use decode::*;
 pub use encode::*;
+
+const ATTRIBUTE_PREFIX: &str = "lx.attr.";
+const ELEMENT_PREFIX: &str = "lx.element.";
+const FREQUENCY_KEY: &str = "lx.frequency";
+
+/// [Codec](crate::Codec) implementation for the LXNav/Naviter file format.
+#[derive(Debug, Clone, Copy)]
+pub struct Lx;
+
+impl crate::Codec for Lx {
+    const FORMAT: crate::Format = crate::Format::Lx;
+
+    fn decode_with_options(
+        data: &[u8],
+        options: &crate::DecodeOptions,
+    ) -> Result<crate::DecodedFile, crate::Error> {
+        let decoded = decode_file_with_options(data, options)?;
+        Ok(crate::DecodedFile::new(
+            Self::FORMAT,
+            decoded.version,
+            decoded.records,
+            decoded.extensions,
+        ))
+    }
+
+    fn encode(file: &crate::File) -> Result<Vec<u8>, crate::Error> {
+        Ok(encode_file(file)?)
+    }
+
+    fn encode_with_report(
+        file: &crate::File,
+    ) -> Result<(Vec<u8>, crate::EncodeReport), crate::Error> {
+        // the XML format has no length limits
+        Ok((encode_file(file)?, crate::EncodeReport::default()))
+    }
+}
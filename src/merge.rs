@@ -0,0 +1,315 @@
+use crate::{Field, File, FlarmId, Record};
+use std::collections::{BTreeMap, HashMap, HashSet};
+
+/// Strategy to select a field value if multiple sources contain a record.
+#[derive(Debug, Default, Eq, PartialEq, Hash, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
+pub enum MergePolicy {
+    /// Use the first non-empty value, in order of priority.
+    #[default]
+    FirstNonEmpty,
+    /// Use the value of the source with the highest priority, even if it is
+    /// empty.
+    HighestPriority,
+    /// Use the longest value. If multiple values have the same length, the
+    /// one with the highest priority is used.
+    Longest,
+}
+
+/// A [File] and its priority for [merge].
+#[derive(Debug, Clone, Copy)]
+pub struct MergeSource<'a> {
+    pub file: &'a File,
+    /// Sources with higher priority are preferred. Sources with the same
+    /// priority are preferred in the order they are passed to [merge].
+    pub priority: i32,
+}
+
+/// Options for [merge].
+#[derive(Debug, Default, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
+pub struct MergeOptions {
+    /// Policy for all fields without an entry in `field_policies`.
+    pub default_policy: MergePolicy,
+    pub field_policies: HashMap<Field, MergePolicy>,
+}
+
+impl MergeOptions {
+    /// Returns the policy for the given field.
+    pub fn policy(&self, field: Field) -> MergePolicy {
+        self.field_policies
+            .get(&field)
+            .copied()
+            .unwrap_or(self.default_policy)
+    }
+}
+
+/// Result of [merge].
+#[derive(Debug, Eq, PartialEq, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct MergeResult {
+    pub file: File,
+    /// Fields for which the sources contained different non-empty values,
+    /// sorted by FLARM ID.
+    pub conflicts: Vec<Conflict>,
+}
+
+/// A field for which the sources contained different non-empty values.
+#[derive(Debug, Eq, PartialEq, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Conflict {
+    pub flarm_id: FlarmId,
+    pub field: Field,
+    /// The non-empty values of all sources containing the record, in order
+    /// of priority.
+    pub values: Vec<ConflictValue>,
+    /// The value that has been used for the merged record.
+    pub chosen: String,
+}
+
+#[derive(Debug, Eq, PartialEq, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ConflictValue {
+    /// Index of the source in the slice passed to [merge].
+    pub source: usize,
+    pub value: String,
+}
+
+/// Merges the records of multiple files, matching them by FLARM ID.
+///
+/// The merged file contains the records of all sources, sorted by FLARM ID.
+/// The field values are selected according to the [MergePolicy] of each
+/// field, while the [extensions](Record::extensions) are taken from the
+/// source with the highest priority. The merged file uses the highest version
+/// and the extensions of the source with the highest priority.
+///
+/// If a file contains the same FLARM ID multiple times, only the first
+/// record is used.
+///
+/// # Examples
+///
+#[cfg_attr(feature = "xcsoar", doc = "```")]
+#[cfg_attr(not(feature = "xcsoar"), doc = "```ignore")]
+/// # use flarmnet::{MergeOptions, MergeSource};
+/// let content = std::fs::read("tests/fixtures/data.fln").unwrap();
+/// let official = flarmnet::decode_any(&content).unwrap().into_file();
+///
+/// let mut club = official.clone();
+/// club.records.truncate(1);
+/// club.records[0].call_sign = "MU".to_string();
+///
+/// let sources = [
+///     MergeSource { file: &official, priority: 0 },
+///     MergeSource { file: &club, priority: 10 },
+/// ];
+/// let result = flarmnet::merge(&sources, &MergeOptions::default());
+/// assert_eq!(result.file.records.len(), 3);
+/// assert_eq!(result.file.records[0].call_sign, "MU");
+/// assert!(result.conflicts.is_empty());
+/// ```
+pub fn merge(sources: &[MergeSource<'_>], options: &MergeOptions) -> MergeResult {
+    // source indices, sorted by priority
+    let mut order: Vec<usize> = (0..sources.len()).collect();
+    order.sort_by_key(|&index| std::cmp::Reverse(sources[index].priority));
+
+    // records by FLARM ID, in order of priority
+    let mut records: BTreeMap<FlarmId, Vec<(usize, &Record)>> = BTreeMap::new();
+    for &index in &order {
+        let mut seen = HashSet::new();
+        for record in &sources[index].file.records {
+            if seen.insert(record.flarm_id) {
+                records
+                    .entry(record.flarm_id)
+                    .or_default()
+                    .push((index, record));
+            }
+        }
+    }
+
+    let mut conflicts = Vec::new();
+    let merged = records
+        .into_iter()
+        .map(|(flarm_id, candidates)| {
+            let mut merged = candidates[0].1.clone();
+            for &field in Field::ALL {
+                let chosen = select(&candidates, field, options.policy(field));
+                copy_field(field, chosen, &mut merged);
+
+                if let Some(values) = conflicting_values(&candidates, field) {
+                    conflicts.push(Conflict {
+                        flarm_id,
+                        field,
+                        values,
+                        chosen: field.get(chosen).into_owned(),
+                    });
+                }
+            }
+            merged
+        })
+        .collect();
+
+    let first = order.first().map(|&index| sources[index].file);
+    let file = File {
+        version: sources.iter().map(|it| it.file.version).max().unwrap_or(0),
+        records: merged,
+        extensions: first.map(|it| it.extensions.clone()).unwrap_or_default(),
+    };
+
+    MergeResult { file, conflicts }
+}
+
+/// Selects the record to take the field value from.
+fn select<'a>(candidates: &[(usize, &'a Record)], field: Field, policy: MergePolicy) -> &'a Record {
+    let highest_priority = candidates[0].1;
+    let mut non_empty = candidates
+        .iter()
+        .map(|(_, record)| *record)
+        .filter(|record| !field.get(record).is_empty());
+
+    match policy {
+        MergePolicy::HighestPriority => highest_priority,
+        MergePolicy::FirstNonEmpty => non_empty.next().unwrap_or(highest_priority),
+        MergePolicy::Longest => non_empty
+            // `max_by_key` returns the last maximum, so reverse the order to
+            // prefer higher priorities
+            .rev()
+            .max_by_key(|record| field.get(record).chars().count())
+            .unwrap_or(highest_priority),
+    }
+}
+
+/// Returns the non-empty values of the field, if there are at least two
+/// different ones.
+fn conflicting_values(candidates: &[(usize, &Record)], field: Field) -> Option<Vec<ConflictValue>> {
+    let values: Vec<_> = candidates
+        .iter()
+        .map(|(source, record)| ConflictValue {
+            source: *source,
+            value: field.get(record).into_owned(),
+        })
+        .filter(|it| !it.value.is_empty())
+        .collect();
+
+    let first = &values.first()?.value;
+    values.iter().any(|it| &it.value != first).then_some(values)
+}
+
+fn copy_field(field: Field, from: &Record, to: &mut Record) {
+    match field {
+        Field::PilotName => to.pilot_name.clone_from(&from.pilot_name),
+        Field::Airfield => to.airfield.clone_from(&from.airfield),
+        Field::PlaneType => to.plane_type.clone_from(&from.plane_type),
+        Field::Registration => to.registration.clone_from(&from.registration),
+        Field::CallSign => to.call_sign.clone_from(&from.call_sign),
+        Field::Frequency => to.frequency = from.frequency,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{MergeOptions, MergePolicy, MergeSource, merge};
+    use crate::Field;
+    use crate::test_util::{make_file, make_record};
+    use insta::assert_debug_snapshot;
+
+    #[test]
+    fn merge_combines_records_of_all_sources() {
+        let a = make_file(1, vec![make_record("DD1234", "D-KABC", "")]);
+        let b = make_file(2, vec![make_record("3EE3C7", "D-0816", "SG")]);
+        let sources = [
+            MergeSource {
+                file: &a,
+                priority: 0,
+            },
+            MergeSource {
+                file: &b,
+                priority: 0,
+            },
+        ];
+
+        let result = merge(&sources, &MergeOptions::default());
+        assert_eq!(result.file.version, 2);
+        let ids: Vec<_> = result
+            .file
+            .records
+            .iter()
+            .map(|it| it.flarm_id.to_string())
+            .collect();
+        assert_eq!(ids, ["3EE3C7", "DD1234"]);
+        assert!(result.conflicts.is_empty());
+    }
+
+    #[test]
+    fn merge_uses_field_policies() {
+        let official = make_file(1, vec![make_record("3EE3C7", "D-0816", "SG")]);
+        let club = make_file(1, vec![make_record("3EE3C7", "", "S")]);
+        let sources = [
+            MergeSource {
+                file: &official,
+                priority: 0,
+            },
+            MergeSource {
+                file: &club,
+                priority: 1,
+            },
+        ];
+
+        let result = merge(&sources, &MergeOptions::default());
+        assert_eq!(result.file.records[0].registration, "D-0816");
+        assert_eq!(result.file.records[0].call_sign, "S");
+
+        let options = MergeOptions {
+            default_policy: MergePolicy::HighestPriority,
+            field_policies: [(Field::CallSign, MergePolicy::Longest)].into(),
+        };
+        let result = merge(&sources, &options);
+        assert_eq!(result.file.records[0].registration, "");
+        assert_eq!(result.file.records[0].call_sign, "SG");
+    }
+
+    #[test]
+    fn merge_reports_conflicts() {
+        let official = make_file(1, vec![make_record("3EE3C7", "D-0816", "SG")]);
+        let club = make_file(1, vec![make_record("3EE3C7", "", "S")]);
+        let contest = make_file(1, vec![make_record("3EE3C7", "D-0816", "")]);
+        let sources = [
+            MergeSource {
+                file: &official,
+                priority: 0,
+            },
+            MergeSource {
+                file: &club,
+                priority: 2,
+            },
+            MergeSource {
+                file: &contest,
+                priority: 1,
+            },
+        ];
+
+        let result = merge(&sources, &MergeOptions::default());
+        assert_debug_snapshot!(result.conflicts, @r###"
+        [
+            Conflict {
+                flarm_id: FlarmId(
+                    3EE3C7,
+                ),
+                field: CallSign,
+                values: [
+                    ConflictValue {
+                        source: 1,
+                        value: "S",
+                    },
+                    ConflictValue {
+                        source: 0,
+                        value: "SG",
+                    },
+                ],
+                chosen: "S",
+            },
+        ]
+        "###);
+    }
+}
@@ -0,0 +1,261 @@
+use super::COLUMNS;
+use super::device::{Device, DeviceType, decode_flag};
+use crate::Record;
+use std::io::Read;
+use thiserror::Error;
+
+#[derive(Error, Debug)]
+pub enum DecodeError {
+    #[error(transparent)]
+    Csv(#[from] csv::Error),
+    #[error(transparent)]
+    Json(#[from] serde_json::Error),
+    #[error("missing column: {0}")]
+    MissingColumn(&'static str),
+    #[error("missing field: {0}")]
+    MissingField(&'static str),
+    /// A JSON field does not contain a string.
+    #[error("invalid value for field {field}: {value}")]
+    InvalidValue { field: &'static str, value: String },
+    #[error("invalid device type: {0}")]
+    InvalidDeviceType(String),
+    #[error("invalid FLARM id: {0}")]
+    InvalidFlarmId(String),
+    #[error("invalid flag: {0}")]
+    InvalidFlag(String),
+}
+
+#[derive(Debug)]
+pub struct DecodedFile {
+    pub records: Vec<Result<Record, DecodeError>>,
+}
+
+/// Decodes an OGN DDB file.
+///
+/// The registration and competition number of devices that are not
+/// identified are cleared, see [module documentation](super).
+///
+/// # Examples
+///
+/// ```
+/// let file = r#"#DEVICE_TYPE,DEVICE_ID,AIRCRAFT_MODEL,REGISTRATION,CN,TRACKED,IDENTIFIED
+/// 'F','3EE3C7','LS-6','D-0816','SG','Y','Y'
+/// 'I','3D1234','Cessna 172','D-EABC','','Y','N'
+/// "#;
+///
+/// let result = flarmnet::ogn::decode_file(file).unwrap();
+/// let records: Vec<_> = result.records.into_iter().map(Result::unwrap).collect();
+/// assert_eq!(records[0].registration, "D-0816");
+/// assert_eq!(records[1].registration, "");
+/// ```
+pub fn decode_file(file: &str) -> Result<DecodedFile, DecodeError> {
+    decode_reader(file.as_bytes())
+}
+
+/// Decodes an OGN DDB file from any [Read] implementation, see [decode_file].
+pub fn decode_reader<R: Read>(reader: R) -> Result<DecodedFile, DecodeError> {
+    let reader = Reader::new(reader)?;
+    let records = reader
+        .map(|result| result.map(|device| device.to_record()))
+        .collect();
+
+    Ok(DecodedFile { records })
+}
+
+/// Streaming decoder for OGN DDB files, yielding [Devices](Device).
+///
+/// The column order is read from the header line when the reader is created.
+/// If the file has no header line, the default column order is used.
+///
+/// # Examples
+///
+/// ```
+/// let file = r#"#DEVICE_TYPE,DEVICE_ID,AIRCRAFT_MODEL,REGISTRATION,CN,TRACKED,IDENTIFIED
+/// 'I','3D1234','Cessna 172','D-EABC','','Y','N'
+/// "#;
+///
+/// let mut reader = flarmnet::ogn::Reader::new(file.as_bytes()).unwrap();
+/// let device = reader.next().unwrap().unwrap();
+/// assert_eq!(device.device_type, flarmnet::ogn::DeviceType::Icao);
+/// assert_eq!(device.registration, "D-EABC");
+/// assert!(!device.identified);
+/// ```
+pub struct Reader<R: Read> {
+    csv: csv::Reader<R>,
+    columns: [usize; COLUMNS.len()],
+    record: csv::StringRecord,
+    pending: bool,
+}
+
+impl<R: Read> Reader<R> {
+    /// Creates a new reader and reads the header line.
+    pub fn new(inner: R) -> Result<Self, DecodeError> {
+        let mut csv = csv::ReaderBuilder::new()
+            .has_headers(false)
+            .flexible(true)
+            .quote(b'\'')
+            .from_reader(inner);
+
+        let mut record = csv::StringRecord::new();
+        let mut columns = std::array::from_fn(|i| i);
+        let mut pending = csv.read_record(&mut record)?;
+
+        let is_header = record.get(0).is_some_and(|it| it.starts_with('#'));
+        if pending && is_header {
+            for (column, name) in columns.iter_mut().zip(COLUMNS) {
+                *column = record
+                    .iter()
+                    .position(|it| it.trim_start_matches('#').trim() == name)
+                    .ok_or(DecodeError::MissingColumn(name))?;
+            }
+            pending = false;
+        }
+
+        Ok(Self {
+            csv,
+            columns,
+            record,
+            pending,
+        })
+    }
+
+    pub fn into_inner(self) -> R {
+        self.csv.into_inner()
+    }
+
+    fn decode_device(&self) -> Result<Device, DecodeError> {
+        let values = self
+            .columns
+            .map(|column| self.record.get(column).unwrap_or_default().trim());
+
+        decode_device(values)
+    }
+}
+
+impl<R: Read> Iterator for Reader<R> {
+    type Item = Result<Device, DecodeError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if !self.pending {
+            match self.csv.read_record(&mut self.record) {
+                Ok(true) => {}
+                Ok(false) => return None,
+                Err(error) => return Some(Err(error.into())),
+            }
+        }
+        self.pending = false;
+
+        Some(self.decode_device())
+    }
+}
+
+/// Decodes a device from the values of the [columns](COLUMNS).
+pub(super) fn decode_device(values: [&str; COLUMNS.len()]) -> Result<Device, DecodeError> {
+    let [
+        device_type,
+        device_id,
+        aircraft_model,
+        registration,
+        cn,
+        tracked,
+        identified,
+    ] = values;
+
+    let device_type = DeviceType::from_code(device_type)
+        .ok_or_else(|| DecodeError::InvalidDeviceType(device_type.to_string()))?;
+
+    let device_id = device_id
+        .parse()
+        .map_err(|_| DecodeError::InvalidFlarmId(device_id.to_string()))?;
+
+    let flag =
+        |value: &str| decode_flag(value).ok_or_else(|| DecodeError::InvalidFlag(value.to_string()));
+
+    Ok(Device {
+        device_type,
+        device_id,
+        aircraft_model: aircraft_model.to_string(),
+        registration: registration.to_string(),
+        cn: cn.to_string(),
+        tracked: flag(tracked)?,
+        identified: flag(identified)?,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Reader, decode_file};
+    use insta::assert_debug_snapshot;
+
+    #[test]
+    fn decoding_works_without_header() {
+        let file = "'O','ABCDEF','Paraglider','','','N','Y'\n";
+        let devices: Vec<_> = Reader::new(file.as_bytes()).unwrap().collect();
+        assert_debug_snapshot!(devices, @r###"
+        [
+            Ok(
+                Device {
+                    device_type: Ogn,
+                    device_id: FlarmId(
+                        ABCDEF,
+                    ),
+                    aircraft_model: "Paraglider",
+                    registration: "",
+                    cn: "",
+                    tracked: false,
+                    identified: true,
+                },
+            ),
+        ]
+        "###);
+    }
+
+    #[test]
+    fn decoding_uses_column_order_of_header() {
+        let file = "#DEVICE_ID,DEVICE_TYPE,AIRCRAFT_MODEL,REGISTRATION,CN,TRACKED,IDENTIFIED,AIRCRAFT_TYPE\n\
+                    'DD1234','F','ASK-21','D-1234','AB','Y','Y','1'\n";
+        let result = decode_file(file).unwrap();
+        let record = result.records[0].as_ref().unwrap();
+        assert_eq!(record.flarm_id.to_string(), "DD1234");
+        assert_eq!(record.call_sign, "AB");
+        assert!(record.extensions.is_empty());
+    }
+
+    #[test]
+    fn decoding_fails_for_missing_column() {
+        let file = "#DEVICE_TYPE,DEVICE_ID,AIRCRAFT_MODEL,REGISTRATION,CN,TRACKED\n";
+        assert_debug_snapshot!(decode_file(file).unwrap_err(), @r###"
+        MissingColumn(
+            "IDENTIFIED",
+        )
+        "###);
+    }
+
+    #[test]
+    fn decoding_reports_invalid_rows() {
+        let file = "#DEVICE_TYPE,DEVICE_ID,AIRCRAFT_MODEL,REGISTRATION,CN,TRACKED,IDENTIFIED\n\
+                    'X','DD1234','ASK-21','D-1234','AB','Y','Y'\n\
+                    'F','DD123X','ASK-21','D-1234','AB','Y','Y'\n\
+                    'F','DD1234','ASK-21','D-1234','AB','Y','?'\n";
+        let result = decode_file(file).unwrap();
+        assert_debug_snapshot!(result.records, @r###"
+        [
+            Err(
+                InvalidDeviceType(
+                    "X",
+                ),
+            ),
+            Err(
+                InvalidFlarmId(
+                    "DD123X",
+                ),
+            ),
+            Err(
+                InvalidFlag(
+                    "?",
+                ),
+            ),
+        ]
+        "###);
+    }
+}
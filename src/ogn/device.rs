@@ -0,0 +1,162 @@
+use super::{DEVICE_TYPE_KEY, IDENTIFIED_KEY, TRACKED_KEY};
+use crate::{Extensions, FlarmId, Record};
+use std::fmt;
+
+/// The kind of address that is used as device ID.
+#[derive(Debug, Default, Eq, PartialEq, Hash, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
+pub enum DeviceType {
+    /// FLARM radio ID (`F`)
+    #[default]
+    Flarm,
+    /// ICAO 24-bit aircraft address (`I`)
+    Icao,
+    /// OGN tracker ID (`O`)
+    Ogn,
+}
+
+impl DeviceType {
+    /// Returns the single-letter code that is used in the DDB.
+    pub fn code(self) -> &'static str {
+        match self {
+            DeviceType::Flarm => "F",
+            DeviceType::Icao => "I",
+            DeviceType::Ogn => "O",
+        }
+    }
+
+    /// Parses the single-letter code that is used in the DDB.
+    pub fn from_code(code: &str) -> Option<Self> {
+        match code {
+            "F" => Some(DeviceType::Flarm),
+            "I" => Some(DeviceType::Icao),
+            "O" => Some(DeviceType::Ogn),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for DeviceType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.code())
+    }
+}
+
+/// An entry of the OGN device database.
+#[derive(Debug, Eq, PartialEq, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Device {
+    pub device_type: DeviceType,
+    pub device_id: FlarmId,
+    pub aircraft_model: String,
+    pub registration: String,
+    /// Competition number
+    pub cn: String,
+    /// Whether the owner allows the device to be tracked.
+    pub tracked: bool,
+    /// Whether the owner allows the registration and competition number to
+    /// be shown.
+    pub identified: bool,
+}
+
+impl Device {
+    /// Converts the device into a [Record].
+    ///
+    /// The registration and competition number are cleared if the device is
+    /// not [identified](Device::identified). The device type and flags are
+    /// stored in the record extensions, see [module documentation](super).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use flarmnet::ogn::{Device, DeviceType};
+    /// let device = Device {
+    ///     device_type: DeviceType::Flarm,
+    ///     device_id: "3EE3C7".parse().unwrap(),
+    ///     aircraft_model: "LS-6".to_string(),
+    ///     registration: "D-0816".to_string(),
+    ///     cn: "SG".to_string(),
+    ///     tracked: true,
+    ///     identified: false,
+    /// };
+    ///
+    /// let record = device.to_record();
+    /// assert_eq!(record.plane_type, "LS-6");
+    /// assert_eq!(record.registration, "");
+    /// assert_eq!(record.call_sign, "");
+    /// assert_eq!(record.extensions["ogn.identified"], "N");
+    /// ```
+    pub fn to_record(&self) -> Record {
+        let (registration, call_sign) = match self.identified {
+            true => (self.registration.clone(), self.cn.clone()),
+            false => (String::new(), String::new()),
+        };
+
+        let mut extensions = Extensions::new();
+        if self.device_type != DeviceType::Flarm {
+            let value = self.device_type.code().to_string();
+            extensions.insert(DEVICE_TYPE_KEY.to_string(), value);
+        }
+        if !self.tracked {
+            extensions.insert(TRACKED_KEY.to_string(), encode_flag(false).to_string());
+        }
+        if !self.identified {
+            extensions.insert(IDENTIFIED_KEY.to_string(), encode_flag(false).to_string());
+        }
+
+        Record {
+            flarm_id: self.device_id,
+            pilot_name: String::new(),
+            airfield: String::new(),
+            plane_type: self.aircraft_model.clone(),
+            registration,
+            call_sign,
+            frequency: None,
+            extensions,
+        }
+    }
+
+    /// Converts a [Record] into a device, using the device type and flags
+    /// from the record extensions.
+    ///
+    /// Returns the name of the extension if it has an invalid value.
+    pub fn from_record(record: &Record) -> Result<Self, &'static str> {
+        let extensions = &record.extensions;
+
+        let device_type = match extensions.get(DEVICE_TYPE_KEY) {
+            Some(code) => DeviceType::from_code(code).ok_or(DEVICE_TYPE_KEY)?,
+            None => DeviceType::default(),
+        };
+
+        let flag = |key| match extensions.get(key) {
+            Some(value) => decode_flag(value).ok_or(key),
+            None => Ok(true),
+        };
+
+        Ok(Self {
+            device_type,
+            device_id: record.flarm_id,
+            aircraft_model: record.plane_type.clone(),
+            registration: record.registration.clone(),
+            cn: record.call_sign.clone(),
+            tracked: flag(TRACKED_KEY)?,
+            identified: flag(IDENTIFIED_KEY)?,
+        })
+    }
+}
+
+pub(super) fn decode_flag(value: &str) -> Option<bool> {
+    match value {
+        "Y" => Some(true),
+        "N" => Some(false),
+        _ => None,
+    }
+}
+
+pub(super) fn encode_flag(value: bool) -> &'static str {
+    match value {
+        true => "Y",
+        false => "N",
+    }
+}
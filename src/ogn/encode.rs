@@ -0,0 +1,175 @@
+use super::COLUMNS;
+use super::device::{Device, encode_flag};
+use crate::File;
+use std::io::{Cursor, Write};
+use thiserror::Error;
+
+#[derive(Error, Debug)]
+pub enum EncodeError {
+    #[error(transparent)]
+    Io(#[from] std::io::Error),
+    #[error(transparent)]
+    Json(#[from] serde_json::Error),
+    #[error("invalid extension value for {key}: {value}")]
+    InvalidExtension { key: &'static str, value: String },
+}
+
+/// Encodes an OGN DDB file.
+///
+/// The device type and flags are read from the record extensions, see
+/// [module documentation](super).
+///
+/// # Examples
+///
+/// ```
+/// # use flarmnet::Record;
+/// let file = flarmnet::File {
+///     version: 0,
+///     records: vec![
+///         Record {
+///             flarm_id: "3EE3C7".parse().unwrap(),
+///             pilot_name: "Tobias Bieniek".to_string(),
+///             airfield: "EDKA".to_string(),
+///             plane_type: "LS6a".to_string(),
+///             registration: "D-0816".to_string(),
+///             call_sign: "SG".to_string(),
+///             frequency: Some("130.530".parse().unwrap()),
+///             extensions: Default::default(),
+///         }
+///     ],
+///     extensions: Default::default(),
+/// };
+///
+/// let result = flarmnet::ogn::encode_file(&file).unwrap();
+/// assert_eq!(result, br#"#DEVICE_TYPE,DEVICE_ID,AIRCRAFT_MODEL,REGISTRATION,CN,TRACKED,IDENTIFIED
+/// 'F','3EE3C7','LS6a','D-0816','SG','Y','Y'
+/// "#);
+/// ```
+pub fn encode_file(file: &File) -> Result<Vec<u8>, EncodeError> {
+    let mut writer = Writer::new(Cursor::new(Vec::new()));
+    writer.write(file)?;
+    Ok(writer.into_inner().into_inner())
+}
+
+#[derive(Clone)]
+pub struct Writer<W: Write> {
+    writer: W,
+}
+
+impl<W: Write> Writer<W> {
+    pub fn new(inner: W) -> Self {
+        Self { writer: inner }
+    }
+
+    /// Writes the records of the file, ignoring the file version.
+    pub fn write(&mut self, file: &File) -> Result<(), EncodeError> {
+        self.write_devices(&encode_devices(file)?)
+    }
+
+    /// Writes the devices including the registration and competition number
+    /// of devices that are not identified.
+    pub fn write_devices(&mut self, devices: &[Device]) -> Result<(), EncodeError> {
+        writeln!(self.writer, "#{}", COLUMNS.join(","))?;
+
+        for device in devices {
+            let device_id = device.device_id.to_string();
+            let values = [
+                device.device_type.code(),
+                &device_id,
+                &device.aircraft_model,
+                &device.registration,
+                &device.cn,
+                encode_flag(device.tracked),
+                encode_flag(device.identified),
+            ];
+
+            let values: Vec<_> = values.iter().map(|value| quote(value)).collect();
+            writeln!(self.writer, "{}", values.join(","))?;
+        }
+
+        Ok(())
+    }
+
+    pub fn into_inner(self) -> W {
+        self.writer
+    }
+}
+
+/// Converts the records of the file to [Devices](Device), reading the device
+/// type and flags from the record extensions.
+pub(super) fn encode_devices(file: &File) -> Result<Vec<Device>, EncodeError> {
+    file.records
+        .iter()
+        .map(|record| {
+            Device::from_record(record).map_err(|key| EncodeError::InvalidExtension {
+                key,
+                value: record.extensions[key].clone(),
+            })
+        })
+        .collect()
+}
+
+fn quote(value: &str) -> String {
+    format!("'{}'", value.replace('\'', "''"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::encode_file;
+    use crate::ogn::decode_file;
+    use crate::{File, Record};
+    use insta::assert_debug_snapshot;
+
+    fn make_record(extensions: &[(&str, &str)]) -> Record {
+        Record {
+            flarm_id: "DD1234".parse().unwrap(),
+            pilot_name: String::new(),
+            airfield: String::new(),
+            plane_type: "Ka 6'E".to_string(),
+            registration: "D-1234".to_string(),
+            call_sign: String::new(),
+            frequency: None,
+            extensions: extensions
+                .iter()
+                .map(|(key, value)| (key.to_string(), value.to_string()))
+                .collect(),
+        }
+    }
+
+    #[test]
+    fn encoding_round_trips_device_metadata() {
+        let file = File {
+            version: 0,
+            records: vec![make_record(&[
+                ("ogn.device_type", "I"),
+                ("ogn.tracked", "N"),
+            ])],
+            extensions: Default::default(),
+        };
+
+        let encoded = encode_file(&file).unwrap();
+        assert_eq!(
+            String::from_utf8(encoded.clone()).unwrap().lines().nth(1),
+            Some("'I','DD1234','Ka 6''E','D-1234','','N','Y'")
+        );
+
+        let decoded = decode_file(std::str::from_utf8(&encoded).unwrap()).unwrap();
+        assert_eq!(decoded.records[0].as_ref().unwrap(), &file.records[0]);
+    }
+
+    #[test]
+    fn encoding_fails_for_invalid_extension() {
+        let file = File {
+            version: 0,
+            records: vec![make_record(&[("ogn.identified", "maybe")])],
+            extensions: Default::default(),
+        };
+
+        assert_debug_snapshot!(encode_file(&file).unwrap_err(), @r###"
+        InvalidExtension {
+            key: "ogn.identified",
+            value: "maybe",
+        }
+        "###);
+    }
+}
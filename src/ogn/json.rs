@@ -0,0 +1,264 @@
+//! Decoder/Encoder for the JSON export of the OGN device database.
+//!
+//! The file is available for download at <https://ddb.glidernet.org/download/?j=1>
+//! and looks like this:
+//!
+//! ```text
+//! {"devices":[{"device_type":"F","device_id":"3EE3C7","aircraft_model":"LS-6",
+//! "registration":"D-0816","cn":"SG","tracked":"Y","identified":"Y"}]}
+//! ```
+//!
+//! The devices are mapped to [Records](crate::Record) in the same way as for
+//! the CSV export, see [module documentation](super). Unknown fields (e.g.
+//! `aircraft_type` in newer exports) are ignored.
+
+use super::decode::decode_device;
+use super::device::{Device, encode_flag};
+use super::encode::encode_devices;
+use super::{COLUMNS, DecodeError, DecodedFile, EncodeError};
+use crate::File;
+use serde_json::Value;
+use std::io::{Cursor, Read, Write};
+
+/// The JSON field names, in the order of the [columns](COLUMNS).
+const FIELDS: [&str; COLUMNS.len()] = [
+    "device_type",
+    "device_id",
+    "aircraft_model",
+    "registration",
+    "cn",
+    "tracked",
+    "identified",
+];
+
+/// Decodes an OGN DDB JSON file.
+///
+/// The registration and competition number of devices that are not
+/// identified are cleared, see [module documentation](super).
+///
+/// # Examples
+///
+/// ```
+/// let file = r#"{"devices":[
+///     {"device_type":"F","device_id":"3EE3C7","aircraft_model":"LS-6","registration":"D-0816","cn":"SG","tracked":"Y","identified":"Y"},
+///     {"device_type":"I","device_id":"3D1234","aircraft_model":"Cessna 172","registration":"D-EABC","cn":"","tracked":"Y","identified":"N"}
+/// ]}"#;
+///
+/// let result = flarmnet::ogn::json::decode_file(file).unwrap();
+/// let records: Vec<_> = result.records.into_iter().map(Result::unwrap).collect();
+/// assert_eq!(records[0].registration, "D-0816");
+/// assert_eq!(records[1].registration, "");
+/// ```
+pub fn decode_file(file: &str) -> Result<DecodedFile, DecodeError> {
+    decode_reader(file.as_bytes())
+}
+
+/// Decodes an OGN DDB JSON file from any [Read] implementation, see
+/// [decode_file].
+pub fn decode_reader<R: Read>(reader: R) -> Result<DecodedFile, DecodeError> {
+    let records = decode_devices(reader)?
+        .into_iter()
+        .map(|result| result.map(|device| device.to_record()))
+        .collect();
+
+    Ok(DecodedFile { records })
+}
+
+/// Decodes the [Devices](Device) of an OGN DDB JSON file with all of their
+/// metadata.
+pub fn decode_devices<R: Read>(reader: R) -> Result<Vec<Result<Device, DecodeError>>, DecodeError> {
+    let value: Value = serde_json::from_reader(reader)?;
+    let devices = value
+        .get("devices")
+        .and_then(Value::as_array)
+        .ok_or(DecodeError::MissingField("devices"))?;
+
+    Ok(devices.iter().map(decode_json_device).collect())
+}
+
+fn decode_json_device(value: &Value) -> Result<Device, DecodeError> {
+    let mut values = [""; COLUMNS.len()];
+    for (value_ref, name) in values.iter_mut().zip(FIELDS) {
+        let value = value.get(name).ok_or(DecodeError::MissingField(name))?;
+        *value_ref = value
+            .as_str()
+            .ok_or_else(|| DecodeError::InvalidValue {
+                field: name,
+                value: value.to_string(),
+            })?
+            .trim();
+    }
+
+    decode_device(values)
+}
+
+/// Encodes an OGN DDB JSON file.
+///
+/// The device type and flags are read from the record extensions, see
+/// [module documentation](super).
+///
+/// # Examples
+///
+/// ```
+/// # use flarmnet::Record;
+/// let file = flarmnet::File {
+///     version: 0,
+///     records: vec![
+///         Record {
+///             flarm_id: "3EE3C7".parse().unwrap(),
+///             pilot_name: "Tobias Bieniek".to_string(),
+///             airfield: "EDKA".to_string(),
+///             plane_type: "LS6a".to_string(),
+///             registration: "D-0816".to_string(),
+///             call_sign: "SG".to_string(),
+///             frequency: Some("130.530".parse().unwrap()),
+///             extensions: Default::default(),
+///         }
+///     ],
+///     extensions: Default::default(),
+/// };
+///
+/// let result = flarmnet::ogn::json::encode_file(&file).unwrap();
+/// assert_eq!(result, br#"{"devices":[
+/// {"device_type":"F","device_id":"3EE3C7","aircraft_model":"LS6a","registration":"D-0816","cn":"SG","tracked":"Y","identified":"Y"}
+/// ]}
+/// "#);
+/// ```
+pub fn encode_file(file: &File) -> Result<Vec<u8>, EncodeError> {
+    let mut writer = Writer::new(Cursor::new(Vec::new()));
+    writer.write(file)?;
+    Ok(writer.into_inner().into_inner())
+}
+
+#[derive(Clone)]
+pub struct Writer<W: Write> {
+    writer: W,
+}
+
+impl<W: Write> Writer<W> {
+    pub fn new(inner: W) -> Self {
+        Self { writer: inner }
+    }
+
+    /// Writes the records of the file, ignoring the file version.
+    pub fn write(&mut self, file: &File) -> Result<(), EncodeError> {
+        self.write_devices(&encode_devices(file)?)
+    }
+
+    /// Writes the devices including the registration and competition number
+    /// of devices that are not identified.
+    ///
+    /// Each device is written on a separate line, with the fields in the
+    /// same order as in the official export.
+    pub fn write_devices(&mut self, devices: &[Device]) -> Result<(), EncodeError> {
+        write!(self.writer, "{{\"devices\":[")?;
+
+        for (index, device) in devices.iter().enumerate() {
+            let device_id = device.device_id.to_string();
+            let values = [
+                device.device_type.code(),
+                &device_id,
+                &device.aircraft_model,
+                &device.registration,
+                &device.cn,
+                encode_flag(device.tracked),
+                encode_flag(device.identified),
+            ];
+
+            let separator = if index == 0 { "" } else { "," };
+            write!(self.writer, "{separator}\n{{")?;
+            for (index, (name, value)) in FIELDS.iter().zip(values).enumerate() {
+                let separator = if index == 0 { "" } else { "," };
+                write!(self.writer, "{separator}\"{name}\":")?;
+                serde_json::to_writer(&mut self.writer, value)?;
+            }
+            write!(self.writer, "}}")?;
+        }
+
+        writeln!(self.writer, "\n]}}")?;
+        Ok(())
+    }
+
+    pub fn into_inner(self) -> W {
+        self.writer
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{decode_devices, decode_file, encode_file};
+    use crate::{File, Record};
+    use insta::assert_debug_snapshot;
+
+    #[test]
+    fn decoding_reports_invalid_devices() {
+        let file = r#"{"devices":[
+            {"device_type":"F","device_id":"DD1234","aircraft_model":"ASK-21","registration":"D-1234","cn":"AB","tracked":"Y"},
+            {"device_type":"F","device_id":"DD1234","aircraft_model":"ASK-21","registration":"D-1234","cn":"AB","tracked":"Y","identified":true},
+            {"device_type":"X","device_id":"DD1234","aircraft_model":"ASK-21","registration":"D-1234","cn":"AB","tracked":"Y","identified":"Y"}
+        ]}"#;
+        let devices = decode_devices(file.as_bytes()).unwrap();
+        assert_debug_snapshot!(devices, @r###"
+        [
+            Err(
+                MissingField(
+                    "identified",
+                ),
+            ),
+            Err(
+                InvalidValue {
+                    field: "identified",
+                    value: "true",
+                },
+            ),
+            Err(
+                InvalidDeviceType(
+                    "X",
+                ),
+            ),
+        ]
+        "###);
+    }
+
+    #[test]
+    fn decoding_fails_without_devices() {
+        assert_debug_snapshot!(decode_file(r#"{"aircraft":[]}"#).unwrap_err(), @r###"
+        MissingField(
+            "devices",
+        )
+        "###);
+        assert!(matches!(
+            decode_file("{\"devices\":[").unwrap_err(),
+            crate::ogn::DecodeError::Json(_)
+        ));
+    }
+
+    #[test]
+    fn encoding_round_trips_device_metadata() {
+        let file = File {
+            version: 0,
+            records: vec![Record {
+                flarm_id: "DD1234".parse().unwrap(),
+                pilot_name: String::new(),
+                airfield: String::new(),
+                plane_type: "Ka 6\"E".to_string(),
+                registration: "D-1234".to_string(),
+                call_sign: String::new(),
+                frequency: None,
+                extensions: [("ogn.device_type".to_string(), "O".to_string())].into(),
+            }],
+            extensions: Default::default(),
+        };
+
+        let encoded = encode_file(&file).unwrap();
+        assert_eq!(
+            String::from_utf8(encoded.clone()).unwrap().lines().nth(1),
+            Some(
+                r#"{"device_type":"O","device_id":"DD1234","aircraft_model":"Ka 6\"E","registration":"D-1234","cn":"","tracked":"Y","identified":"Y"}"#
+            )
+        );
+
+        let decoded = decode_file(std::str::from_utf8(&encoded).unwrap()).unwrap();
+        assert_eq!(decoded.records[0].as_ref().unwrap(), &file.records[0]);
+    }
+}
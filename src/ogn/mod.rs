@@ -0,0 +1,60 @@
+//! Decoder/Encoder for the CSV export of the Open Glider Network (OGN)
+//! device database (DDB).
+//!
+//! The file is available for download at <https://ddb.glidernet.org/download/>
+//! and looks like this:
+//!
+//! ```text
+//! #DEVICE_TYPE,DEVICE_ID,AIRCRAFT_MODEL,REGISTRATION,CN,TRACKED,IDENTIFIED
+//! 'F','3EE3C7','LS-6','D-0816','SG','Y','Y'
+//! 'I','3D1234','Cessna 172','D-EABC','','Y','N'
+//! ```
+//!
+//! The [decode_file] and [decode_reader] functions can be used to decode such
+//! files into [Records](crate::Record), while the [Reader] yields the
+//! [Devices](Device) with all of their metadata. The [encode_file] function
+//! can be used to write such files.
+//!
+//! The JSON export of the DDB is supported by the [json] module.
+//!
+//! Unknown columns (e.g. `AIRCRAFT_TYPE` in newer exports) are ignored.
+//!
+//! ## Privacy
+//!
+//! Owners can mark their devices as not identified, in which case the
+//! registration and competition number must not be shown. [Device::to_record]
+//! and therefore the [decode_file] and [decode_reader] functions clear these
+//! fields for devices that are not identified.
+//!
+//! ## Extensions
+//!
+//! The decoder stores the following [Extensions](crate::Extensions) if they
+//! differ from the defaults (`F`, `Y` and `Y`), and the encoder writes them
+//! back:
+//!
+//! - `ogn.device_type` (record): `F` (FLARM), `I` (ICAO) or `O` (OGN)
+//! - `ogn.tracked` (record): `Y` or `N`
+//! - `ogn.identified` (record): `Y` or `N`
+
+mod decode;
+mod device;
+mod encode;
+pub mod json;
+
+pub use decode::*;
+pub use device::*;
+pub use encode::*;
+
+const DEVICE_TYPE_KEY: &str = "ogn.device_type";
+const TRACKED_KEY: &str = "ogn.tracked";
+const IDENTIFIED_KEY: &str = "ogn.identified";
+
+const COLUMNS: [&str; 7] = [
+    "DEVICE_TYPE",
+    "DEVICE_ID",
+    "AIRCRAFT_MODEL",
+    "REGISTRATION",
+    "CN",
+    "TRACKED",
+    "IDENTIFIED",
+];
@@ -0,0 +1,40 @@
+use crate::{Field, FlarmId};
+
+/// Lossy changes that have been made while encoding a file.
+///
+/// Returned by [Format::encode_with_report](crate::Format::encode_with_report)
+/// and the `encode_file_with_report()` functions of the format modules.
+#[derive(Debug, Default, Eq, PartialEq, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct EncodeReport {
+    /// Fields that did not fit into the format, in the order they have been
+    /// written.
+    pub truncated: Vec<TruncatedField>,
+}
+
+impl EncodeReport {
+    /// Returns `true` if the file has been encoded without any losses.
+    pub fn is_empty(&self) -> bool {
+        self.truncated.is_empty()
+    }
+
+    #[cfg(any(feature = "tdb", feature = "xcsoar"))]
+    pub(crate) fn truncate(&mut self, record: &crate::Record, field: Field, written: &str) {
+        self.truncated.push(TruncatedField {
+            flarm_id: record.flarm_id,
+            field,
+            original: field.get(record).into_owned(),
+            written: written.to_string(),
+        });
+    }
+}
+
+/// A field value that has been truncated by the encoder.
+#[derive(Debug, Eq, PartialEq, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct TruncatedField {
+    pub flarm_id: FlarmId,
+    pub field: Field,
+    pub original: String,
+    pub written: String,
+}
@@ -0,0 +1,167 @@
+use super::consts::*;
+use super::decode::{ReaderState, decode_header, into_decoded_file, read_error};
+use super::encode::{encode_header, encode_record};
+use super::{DecodeError, DecodedFile, EncodeError};
+use crate::{DecodeOptions, EncodeReport, Extensions, File, Record};
+use tokio::io::{AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt};
+
+/// Decodes a TDB file from any [AsyncRead] implementation.
+///
+/// # Examples
+///
+/// ```
+/// # #[tokio::main(flavor = "current_thread")]
+/// # async fn main() {
+/// let file = tokio::fs::File::open("tests/fixtures/flarmnet.tdb").await.unwrap();
+/// let result = flarmnet::tdb::decode_async_reader(file).await.unwrap();
+/// assert_eq!(result.version, 28592);
+/// # }
+/// ```
+pub async fn decode_async_reader<R: AsyncRead + Unpin>(
+    reader: R,
+) -> Result<DecodedFile, DecodeError> {
+    decode_async_reader_with_options(reader, &DecodeOptions::default()).await
+}
+
+/// Decodes a TDB file from any [AsyncRead] implementation with the given
+/// [DecodeOptions], see [decode_reader_with_options](super::decode_reader_with_options).
+pub async fn decode_async_reader_with_options<R: AsyncRead + Unpin>(
+    reader: R,
+    options: &DecodeOptions,
+) -> Result<DecodedFile, DecodeError> {
+    let mut reader = AsyncReader::with_options(reader, options).await?;
+    let mut records = Vec::new();
+    while let Some(result) = reader.next().await {
+        records.push(result);
+    }
+
+    let version = reader.version();
+    let extensions = reader.state.extensions;
+    into_decoded_file(version, records, extensions, options)
+}
+
+/// Async version of [Reader](super::Reader) for [AsyncRead]
+/// implementations.
+///
+/// The records are read and decoded one at a time by [AsyncReader::next].
+///
+/// # Examples
+///
+/// ```
+/// # #[tokio::main(flavor = "current_thread")]
+/// # async fn main() {
+/// let file = tokio::fs::File::open("tests/fixtures/flarmnet.tdb").await.unwrap();
+/// let mut reader = flarmnet::tdb::AsyncReader::new(file).await.unwrap();
+/// assert_eq!(reader.record_count(), 3);
+///
+/// while let Some(result) = reader.next().await {
+///     let record = result.unwrap();
+///     println!("{}: {}", record.flarm_id, record.registration);
+/// }
+/// # }
+/// ```
+pub struct AsyncReader<R: AsyncRead + Unpin> {
+    inner: R,
+    state: ReaderState,
+}
+
+impl<R: AsyncRead + Unpin> AsyncReader<R> {
+    /// Creates a new reader and reads the file header and the FLARM ID index,
+    /// see [Reader::new](super::Reader::new).
+    pub async fn new(inner: R) -> Result<Self, DecodeError> {
+        Self::with_options(inner, &DecodeOptions::default()).await
+    }
+
+    /// Creates a new reader with the given [DecodeOptions], see
+    /// [AsyncReader::new].
+    pub async fn with_options(mut inner: R, options: &DecodeOptions) -> Result<Self, DecodeError> {
+        let mut header = [0u8; HEADER_SIZE];
+        inner.read_exact(&mut header).await.map_err(read_error)?;
+        let (version, record_count) = decode_header(&header)?;
+
+        let mut index = Vec::new();
+        let mut entry = [0u8; INDEX_ENTRY_SIZE];
+        for _ in 0..record_count {
+            inner.read_exact(&mut entry).await.map_err(read_error)?;
+            index.push(u32::from_le_bytes(entry));
+        }
+
+        let mut padding = [0u8; PADDING_SIZE];
+        inner.read_exact(&mut padding).await.map_err(read_error)?;
+
+        let state = ReaderState::new(version, index, &padding, options)?;
+        Ok(Self { inner, state })
+    }
+
+    /// Returns the file version.
+    pub fn version(&self) -> u32 {
+        self.state.version
+    }
+
+    /// Returns the number of records according to the file header.
+    pub fn record_count(&self) -> usize {
+        self.state.index.len()
+    }
+
+    /// Returns the file-level [Extensions], see [module documentation](super).
+    pub fn extensions(&self) -> &Extensions {
+        &self.state.extensions
+    }
+
+    /// Reads and decodes the next record, or returns `None` if all records
+    /// have been read.
+    pub async fn next(&mut self) -> Option<Result<Record, DecodeError>> {
+        if self.state.is_done() {
+            return None;
+        }
+
+        let mut data = [0u8; RECORD_SIZE];
+        let result = self.inner.read_exact(&mut data).await;
+        let data = result.map(|_| data).map_err(read_error);
+        Some(self.state.decode_next(data))
+    }
+
+    pub fn into_inner(self) -> R {
+        self.inner
+    }
+}
+
+/// Async version of [Writer](super::Writer) for [AsyncWrite] implementations.
+///
+/// The records are encoded one at a time, and the inner writer is flushed
+/// after the whole file has been written.
+pub struct AsyncWriter<W: AsyncWrite + Unpin> {
+    writer: W,
+}
+
+impl<W: AsyncWrite + Unpin> AsyncWriter<W> {
+    pub fn new(inner: W) -> Self {
+        Self { writer: inner }
+    }
+
+    pub async fn write(&mut self, file: &File) -> Result<(), EncodeError> {
+        self.write_with_report(file).await?;
+        Ok(())
+    }
+
+    /// Writes the file and reports the fields that have been truncated.
+    pub async fn write_with_report(&mut self, file: &File) -> Result<EncodeReport, EncodeError> {
+        let mut report = EncodeReport::default();
+        let mut records: Vec<&Record> = file.records.iter().collect();
+        records.sort_by_key(|record| record.flarm_id);
+
+        let header = encode_header(file, &records)?;
+        self.writer.write_all(&header).await?;
+        for record in records {
+            let data = encode_record(record, &mut report)?;
+            self.writer.write_all(&data).await?;
+        }
+        self.writer.flush().await?;
+
+        Ok(report)
+    }
+
+    pub fn into_inner(self) -> W {
+        self.writer
+    }
+}
@@ -12,3 +12,11 @@ pub const AIRFIELD_OFFSET: usize = 48;
 pub const PLANE_TYPE_OFFSET: usize = 64;
 pub const REGISTRATION_OFFSET: usize = 80;
 pub const STRING_FIELD_SIZE: usize = 16;
+
+pub const RESERVED_OFFSET: usize = 8;
+pub const RESERVED_SIZE: usize = 8;
+
+// extension keys, see module documentation
+pub const FREQUENCY_KEY: &str = "tdb.frequency";
+pub const PADDING_KEY: &str = "tdb.padding";
+pub const RESERVED_KEY: &str = "tdb.reserved";
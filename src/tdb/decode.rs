@@ -1,84 +1,371 @@
 use super::consts::*;
-use crate::Record;
+use crate::{DecodeOptions, Extensions, FlarmId, Frequency, ParseFrequencyError, Record};
+use std::io::Read;
 use thiserror::Error;
 
 #[derive(Error, Debug)]
 pub enum DecodeError {
+    #[error(transparent)]
+    Io(#[from] std::io::Error),
     #[error("unexpected end of file")]
     UnexpectedEof,
     #[error("invalid magic number: {0:02x?}")]
     InvalidMagic([u8; 4]),
+    #[error("FLARM id index is not sorted")]
+    UnsortedIndex,
+    #[error("non-zero padding bytes: {0:02x?}")]
+    InvalidPadding([u8; PADDING_SIZE]),
+    #[error("FLARM id {actual:06X} does not match index entry {expected:06X}")]
+    IndexMismatch { expected: u32, actual: u32 },
     #[error("invalid FLARM id: {0}")]
     InvalidFlarmId(u32),
+    #[error(transparent)]
+    InvalidFrequency(#[from] ParseFrequencyError),
     #[error("invalid UTF-8 in {field} field at record offset {offset}")]
     InvalidUtf8 { field: &'static str, offset: usize },
+    /// A record could not be decoded. `index` is the zero-based index of the
+    /// record, `offset` the byte offset of the record in the file.
+    #[error("invalid record at byte offset {offset}")]
+    Record {
+        index: usize,
+        offset: usize,
+        source: Box<DecodeError>,
+    },
 }
 
 #[derive(Debug)]
 pub struct DecodedFile {
     pub version: u32,
     pub records: Vec<Result<Record, DecodeError>>,
+    pub extensions: Extensions,
 }
 
 pub fn decode_file(data: &[u8]) -> Result<DecodedFile, DecodeError> {
-    if data.len() < HEADER_SIZE {
+    decode_reader(data)
+}
+
+/// Decodes a TDB file with the given [DecodeOptions].
+pub fn decode_file_with_options(
+    data: &[u8],
+    options: &DecodeOptions,
+) -> Result<DecodedFile, DecodeError> {
+    decode_reader_with_options(data, options)
+}
+
+/// Decodes a TDB file from any [Read] implementation.
+///
+/// If the file ends before all records announced in the header have been
+/// read, [DecodeError::UnexpectedEof] is returned for the whole file, unless
+/// [accept_truncated_files](DecodeOptions::accept_truncated_files) is set.
+///
+/// # Examples
+///
+/// ```
+/// let file = std::fs::File::open("tests/fixtures/flarmnet.tdb").unwrap();
+/// let result = flarmnet::tdb::decode_reader(std::io::BufReader::new(file)).unwrap();
+/// assert_eq!(result.version, 28592);
+/// assert_eq!(result.records.len(), 3);
+/// ```
+pub fn decode_reader<R: Read>(reader: R) -> Result<DecodedFile, DecodeError> {
+    decode_reader_with_options(reader, &DecodeOptions::default())
+}
+
+/// Decodes a TDB file from any [Read] implementation with the given
+/// [DecodeOptions], see [decode_reader].
+pub fn decode_reader_with_options<R: Read>(
+    reader: R,
+    options: &DecodeOptions,
+) -> Result<DecodedFile, DecodeError> {
+    let reader = Reader::with_options(reader, options)?;
+    let version = reader.version();
+    let extensions = reader.extensions().clone();
+    let records = reader.collect();
+    into_decoded_file(version, records, extensions, options)
+}
+
+/// Collects the results of a [Reader] or [AsyncReader](super::AsyncReader)
+/// into a [DecodedFile].
+pub(super) fn into_decoded_file(
+    version: u32,
+    records: Vec<Result<Record, DecodeError>>,
+    extensions: Extensions,
+    options: &DecodeOptions,
+) -> Result<DecodedFile, DecodeError> {
+    // report truncated files as a whole instead of as a failed last record
+    if let Some(Err(DecodeError::Record { source, .. })) = records.last()
+        && matches!(**source, DecodeError::UnexpectedEof)
+        && !options.accept_truncated_files
+    {
         return Err(DecodeError::UnexpectedEof);
     }
 
-    let magic: [u8; 4] = data[0..4].try_into().unwrap();
+    Ok(DecodedFile {
+        version,
+        records,
+        extensions,
+    })
+}
+
+/// Streaming decoder for TDB files.
+///
+/// The file header is read when the reader is created, while the records
+/// are decoded one at a time when iterating over the reader.
+///
+/// # Examples
+///
+/// ```
+/// let file = std::fs::File::open("tests/fixtures/flarmnet.tdb").unwrap();
+/// let reader = flarmnet::tdb::Reader::new(std::io::BufReader::new(file)).unwrap();
+/// assert_eq!(reader.version(), 28592);
+/// assert_eq!(reader.record_count(), 3);
+///
+/// for result in reader {
+///     let record = result.unwrap();
+///     println!("{}: {}", record.flarm_id, record.registration);
+/// }
+/// ```
+pub struct Reader<R: Read> {
+    inner: R,
+    state: ReaderState,
+}
+
+impl<R: Read> Reader<R> {
+    /// Creates a new reader and reads the file header and the FLARM ID index.
+    ///
+    /// Each record is compared to its index entry when iterating over the
+    /// reader, see [Reader::with_options] for the validation of the index
+    /// itself.
+    pub fn new(inner: R) -> Result<Self, DecodeError> {
+        Self::with_options(inner, &DecodeOptions::default())
+    }
+
+    /// Creates a new reader with the given [DecodeOptions].
+    ///
+    /// Returns an error if the index is not sorted or if the padding after
+    /// the index contains non-zero bytes, unless
+    /// [accept_unsorted_index](DecodeOptions::accept_unsorted_index) or
+    /// [accept_non_zero_padding](DecodeOptions::accept_non_zero_padding) are
+    /// set.
+    pub fn with_options(mut inner: R, options: &DecodeOptions) -> Result<Self, DecodeError> {
+        let mut header = [0u8; HEADER_SIZE];
+        read_exact(&mut inner, &mut header)?;
+        let (version, record_count) = decode_header(&header)?;
+
+        let mut index = Vec::new();
+        let mut entry = [0u8; INDEX_ENTRY_SIZE];
+        for _ in 0..record_count {
+            read_exact(&mut inner, &mut entry)?;
+            index.push(u32::from_le_bytes(entry));
+        }
+
+        let mut padding = [0u8; PADDING_SIZE];
+        read_exact(&mut inner, &mut padding)?;
+
+        let state = ReaderState::new(version, index, &padding, options)?;
+        Ok(Self { inner, state })
+    }
+
+    /// Returns the file version.
+    pub fn version(&self) -> u32 {
+        self.state.version
+    }
+
+    /// Returns the number of records according to the file header.
+    pub fn record_count(&self) -> usize {
+        self.state.index.len()
+    }
+
+    /// Returns the file-level [Extensions], see [module documentation](super).
+    pub fn extensions(&self) -> &Extensions {
+        &self.state.extensions
+    }
+
+    pub fn into_inner(self) -> R {
+        self.inner
+    }
+}
+
+impl<R: Read> Iterator for Reader<R> {
+    type Item = Result<Record, DecodeError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.state.is_done() {
+            return None;
+        }
+
+        let mut data = [0u8; RECORD_SIZE];
+        let data = read_exact(&mut self.inner, &mut data).map(|()| data);
+        Some(self.state.decode_next(data))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (0, Some(self.state.remaining))
+    }
+}
+
+/// File header and position of a [Reader] or
+/// [AsyncReader](super::AsyncReader), which only differ in how the bytes
+/// are read.
+pub(super) struct ReaderState {
+    pub(super) version: u32,
+    pub(super) index: Vec<u32>,
+    pub(super) remaining: usize,
+    pub(super) extensions: Extensions,
+    options: DecodeOptions,
+}
+
+impl ReaderState {
+    /// Validates the FLARM ID index and the padding after the file header.
+    pub(super) fn new(
+        version: u32,
+        index: Vec<u32>,
+        padding: &[u8; PADDING_SIZE],
+        options: &DecodeOptions,
+    ) -> Result<Self, DecodeError> {
+        if !options.accept_unsorted_index && !index.is_sorted() {
+            return Err(DecodeError::UnsortedIndex);
+        }
+        let extensions = decode_padding(padding, options)?;
+
+        Ok(Self {
+            version,
+            remaining: index.len(),
+            index,
+            extensions,
+            options: *options,
+        })
+    }
+
+    /// Returns `true` if all records have been read.
+    pub(super) fn is_done(&self) -> bool {
+        self.remaining == 0
+    }
+
+    /// Decodes the bytes of the next record, or reports the error that
+    /// occurred while reading them.
+    pub(super) fn decode_next(
+        &mut self,
+        data: Result<[u8; RECORD_SIZE], DecodeError>,
+    ) -> Result<Record, DecodeError> {
+        let index = self.index.len() - self.remaining;
+        let expected = self.index[index];
+        self.remaining -= 1;
+
+        let record_error = |error| DecodeError::Record {
+            index,
+            offset: HEADER_SIZE
+                + self.index.len() * INDEX_ENTRY_SIZE
+                + PADDING_SIZE
+                + index * RECORD_SIZE,
+            source: Box::new(error),
+        };
+
+        let data = data.inspect_err(|_| {
+            // the remaining records can't be read either
+            self.remaining = 0;
+        });
+        let result = data.and_then(|data| decode_record(&data, &self.options));
+        let result = result.and_then(|record| check_index_entry(record, expected));
+        result.map_err(record_error)
+    }
+}
+
+/// Validates the magic number and returns the version and record count.
+pub(super) fn decode_header(header: &[u8; HEADER_SIZE]) -> Result<(u32, usize), DecodeError> {
+    let magic: [u8; 4] = header[0..4].try_into().unwrap();
     if magic != MAGIC {
         return Err(DecodeError::InvalidMagic(magic));
     }
 
-    let version = u32::from_le_bytes(data[4..8].try_into().unwrap());
-    let record_count = u32::from_le_bytes(data[8..12].try_into().unwrap()) as usize;
+    let version = u32::from_le_bytes(header[4..8].try_into().unwrap());
+    let record_count = u32::from_le_bytes(header[8..12].try_into().unwrap()) as usize;
+    Ok((version, record_count))
+}
 
-    let expected_size =
-        HEADER_SIZE + record_count * INDEX_ENTRY_SIZE + PADDING_SIZE + record_count * RECORD_SIZE;
-    if data.len() < expected_size {
-        return Err(DecodeError::UnexpectedEof);
+/// Returns the file-level [Extensions] with the padding bytes if they are
+/// not zero, or an error unless
+/// [accept_non_zero_padding](DecodeOptions::accept_non_zero_padding) is set.
+pub(super) fn decode_padding(
+    padding: &[u8; PADDING_SIZE],
+    options: &DecodeOptions,
+) -> Result<Extensions, DecodeError> {
+    let mut extensions = Extensions::new();
+    if padding.iter().any(|&b| b != 0) {
+        if !options.accept_non_zero_padding {
+            return Err(DecodeError::InvalidPadding(*padding));
+        }
+        extensions.insert(PADDING_KEY.to_string(), encode_hex(padding));
     }
+    Ok(extensions)
+}
 
-    let records_offset = HEADER_SIZE + record_count * INDEX_ENTRY_SIZE + PADDING_SIZE;
+/// Returns an error if the record does not match its FLARM ID index entry.
+pub(super) fn check_index_entry(record: Record, expected: u32) -> Result<Record, DecodeError> {
+    let actual = record.flarm_id.value();
+    if actual != expected {
+        return Err(DecodeError::IndexMismatch { expected, actual });
+    }
+    Ok(record)
+}
 
-    let records = (0..record_count)
-        .map(|i| {
-            let offset = records_offset + i * RECORD_SIZE;
-            let record_data: &[u8; 96] = data[offset..offset + RECORD_SIZE].try_into().unwrap();
-            decode_record(record_data)
-        })
-        .collect();
+fn read_exact<R: Read>(reader: &mut R, buf: &mut [u8]) -> Result<(), DecodeError> {
+    reader.read_exact(buf).map_err(read_error)
+}
 
-    Ok(DecodedFile { version, records })
+/// Converts an error of `read_exact()`, reporting the end of the file as
+/// [DecodeError::UnexpectedEof].
+pub(super) fn read_error(error: std::io::Error) -> DecodeError {
+    match error.kind() {
+        std::io::ErrorKind::UnexpectedEof => DecodeError::UnexpectedEof,
+        _ => DecodeError::Io(error),
+    }
 }
 
-fn decode_record(data: &[u8; 96]) -> Result<Record, DecodeError> {
+pub(super) fn decode_record(
+    data: &[u8; 96],
+    options: &DecodeOptions,
+) -> Result<Record, DecodeError> {
     let flarm_id = u32::from_le_bytes(
         data[FLARM_ID_OFFSET..FLARM_ID_OFFSET + 4]
             .try_into()
             .unwrap(),
     );
-    if flarm_id > 0xFFFFFF {
-        return Err(DecodeError::InvalidFlarmId(flarm_id));
-    }
-    let flarm_id = format!("{:06X}", flarm_id);
+    let flarm_id = FlarmId::new(flarm_id).ok_or(DecodeError::InvalidFlarmId(flarm_id))?;
 
     let frequency = u32::from_le_bytes(
         data[FREQUENCY_OFFSET..FREQUENCY_OFFSET + 4]
             .try_into()
             .unwrap(),
     );
-    let frequency = if frequency == 0 {
-        String::new()
-    } else {
-        format!("{}.{:03}", frequency / 1000, frequency % 1000)
+    let mut extensions = Extensions::new();
+    let frequency = match Frequency::from_khz(frequency) {
+        _ if frequency == 0 => None,
+        Ok(frequency) => Some(frequency),
+        Err(_) if options.accept_invalid_frequencies => {
+            let bytes = &data[FREQUENCY_OFFSET..FREQUENCY_OFFSET + 4];
+            extensions.insert(FREQUENCY_KEY.to_string(), encode_hex(bytes));
+            None
+        }
+        Err(error) => return Err(error.into()),
     };
 
-    let call_sign = decode_string(data, CALL_SIGN_OFFSET, "call_sign")?;
-    let pilot_name = decode_string(data, PILOT_NAME_OFFSET, "pilot_name")?;
-    let airfield = decode_string(data, AIRFIELD_OFFSET, "airfield")?;
-    let plane_type = decode_string(data, PLANE_TYPE_OFFSET, "plane_type")?;
-    let registration = decode_string(data, REGISTRATION_OFFSET, "registration")?;
+    let reserved = &data[RESERVED_OFFSET..RESERVED_OFFSET + RESERVED_SIZE];
+    if reserved.iter().any(|&b| b != 0) {
+        extensions.insert(RESERVED_KEY.to_string(), encode_hex(reserved));
+    }
+
+    let decode_string = |offset, field| {
+        let value = decode_string(data, offset, field)?;
+        Ok::<_, DecodeError>(match options.trim_whitespace {
+            true => value.trim().to_string(),
+            false => value,
+        })
+    };
+    let call_sign = decode_string(CALL_SIGN_OFFSET, "call_sign")?;
+    let pilot_name = decode_string(PILOT_NAME_OFFSET, "pilot_name")?;
+    let airfield = decode_string(AIRFIELD_OFFSET, "airfield")?;
+    let plane_type = decode_string(PLANE_TYPE_OFFSET, "plane_type")?;
+    let registration = decode_string(REGISTRATION_OFFSET, "registration")?;
 
     Ok(Record {
         flarm_id,
@@ -88,9 +375,14 @@ fn decode_record(data: &[u8; 96]) -> Result<Record, DecodeError> {
         registration,
         call_sign,
         frequency,
+        extensions,
     })
 }
 
+fn encode_hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{b:02x}")).collect()
+}
+
 fn decode_string(
     data: &[u8; 96],
     offset: usize,
@@ -165,7 +457,7 @@ mod tests {
         data.extend_from_slice(&1u32.to_le_bytes()); // version = 1
         data.extend_from_slice(&n.to_le_bytes());
 
-        // index (dummy sorted flarm_ids)
+        // index
         for record in records {
             let flarm_id = u32::from_le_bytes(record[0..4].try_into().unwrap());
             data.extend_from_slice(&flarm_id.to_le_bytes());
@@ -225,13 +517,20 @@ mod tests {
         assert_eq!(result.records.len(), 1);
         assert_debug_snapshot!(result.records[0].as_ref().unwrap(), @r###"
         Record {
-            flarm_id: "3EE3C7",
+            flarm_id: FlarmId(
+                3EE3C7,
+            ),
             pilot_name: "",
             airfield: "EDKA",
             plane_type: "LS6a",
             registration: "D-0816",
             call_sign: "SG",
-            frequency: "123.500",
+            frequency: Some(
+                Frequency(
+                    123.500,
+                ),
+            ),
+            extensions: {},
         }
         "###);
     }
@@ -242,7 +541,7 @@ mod tests {
         let data = make_valid_file(&[record]);
         let result = decode_file(&data).unwrap();
         let record = result.records[0].as_ref().unwrap();
-        assert_eq!(record.frequency, "");
+        assert_eq!(record.frequency, None);
     }
 
     #[test]
@@ -254,9 +553,13 @@ mod tests {
         assert_debug_snapshot!(
             result.records[0].as_ref().unwrap_err(),
             @r###"
-        InvalidFlarmId(
-            16777216,
-        )
+        Record {
+            index: 0,
+            offset: 24,
+            source: InvalidFlarmId(
+                16777216,
+            ),
+        }
         "###
         );
     }
@@ -272,11 +575,158 @@ mod tests {
         assert_debug_snapshot!(
             result.records[0].as_ref().unwrap_err(),
             @r###"
-        InvalidUtf8 {
-            field: "call_sign",
-            offset: 16,
+        Record {
+            index: 0,
+            offset: 24,
+            source: InvalidUtf8 {
+                field: "call_sign",
+                offset: 16,
+            },
+        }
+        "###
+        );
+    }
+
+    #[test]
+    fn decoding_keeps_records_with_invalid_frequency() {
+        let record = make_record(0x000001, 108000, b"", b"", b"", b"D-0816");
+        let data = make_valid_file(&[record]);
+
+        let result = decode_file(&data).unwrap();
+        let record = result.records[0].as_ref().unwrap();
+        assert_eq!(record.registration, "D-0816");
+        assert_eq!(record.frequency, None);
+        assert_eq!(record.extensions[FREQUENCY_KEY], "e0a50100");
+
+        let result = decode_file_with_options(&data, &DecodeOptions::STRICT).unwrap();
+        assert_debug_snapshot!(
+            result.records[0].as_ref().unwrap_err(),
+            @r###"
+        Record {
+            index: 0,
+            offset: 24,
+            source: InvalidFrequency(
+                OutOfBand(
+                    "108.000",
+                ),
+            ),
         }
         "###
         );
     }
+
+    #[test]
+    fn decoding_fails_for_non_zero_padding() {
+        let record = make_record(0x000001, 0, b"", b"", b"", b"");
+        let mut data = make_valid_file(&[record]);
+        data[HEADER_SIZE + INDEX_ENTRY_SIZE] = 0x42;
+
+        let result = decode_file(&data);
+        assert_debug_snapshot!(result.unwrap_err(), @r###"
+        InvalidPadding(
+            [
+                66,
+                0,
+                0,
+                0,
+                0,
+                0,
+                0,
+                0,
+            ],
+        )
+        "###);
+    }
+
+    #[test]
+    fn decoding_fails_for_unsorted_index() {
+        let records = [
+            make_record(0x000002, 0, b"", b"", b"", b""),
+            make_record(0x000001, 0, b"", b"", b"", b""),
+        ];
+        let data = make_valid_file(&records);
+
+        let result = decode_file(&data);
+        assert_debug_snapshot!(result.unwrap_err(), @"UnsortedIndex");
+
+        let result = decode_file_with_options(&data, &DecodeOptions::LENIENT).unwrap();
+        assert_eq!(result.records.len(), 2);
+        assert!(result.records.iter().all(Result::is_ok));
+    }
+
+    #[test]
+    fn decoding_reports_index_mismatch() {
+        let records = [
+            make_record(0x000001, 0, b"", b"", b"", b""),
+            make_record(0x000002, 0, b"", b"", b"", b""),
+        ];
+        let mut data = make_valid_file(&records);
+        // change the second index entry
+        data[HEADER_SIZE + INDEX_ENTRY_SIZE] = 0x03;
+
+        let result = decode_file(&data).unwrap();
+        assert!(result.records[0].is_ok());
+        assert_debug_snapshot!(result.records[1], @r###"
+        Err(
+            Record {
+                index: 1,
+                offset: 124,
+                source: IndexMismatch {
+                    expected: 3,
+                    actual: 2,
+                },
+            },
+        )
+        "###);
+    }
+
+    #[test]
+    fn decoding_keeps_reserved_and_padding_bytes() {
+        let mut record = make_record(0x000001, 0, b"", b"", b"", b"");
+        record[RESERVED_OFFSET] = 0x01;
+        record[RESERVED_OFFSET + 7] = 0xFF;
+        let mut data = make_valid_file(&[record]);
+        data[HEADER_SIZE + INDEX_ENTRY_SIZE] = 0x42;
+
+        let result = decode_file_with_options(&data, &DecodeOptions::LENIENT).unwrap();
+        assert_debug_snapshot!(result.extensions, @r###"
+        {
+            "tdb.padding": "4200000000000000",
+        }
+        "###);
+        assert_debug_snapshot!(result.records[0].as_ref().unwrap().extensions, @r###"
+        {
+            "tdb.reserved": "01000000000000ff",
+        }
+        "###);
+    }
+
+    #[test]
+    fn lenient_decoding_accepts_truncated_files() {
+        let records = [
+            make_record(0x000001, 0, b"", b" EDKA ", b"", b""),
+            make_record(0x000002, 0, b"", b"", b"", b""),
+        ];
+        let mut data = make_valid_file(&records);
+        data.truncate(data.len() - 1);
+
+        let result = decode_file_with_options(&data, &DecodeOptions::LENIENT).unwrap();
+        assert_eq!(result.records[0].as_ref().unwrap().airfield, "EDKA");
+        assert_debug_snapshot!(result.records[1], @r###"
+        Err(
+            Record {
+                index: 1,
+                offset: 124,
+                source: UnexpectedEof,
+            },
+        )
+        "###);
+
+        let result = decode_file_with_options(&data, &DecodeOptions::STRICT);
+        assert_debug_snapshot!(result.unwrap_err(), @"UnexpectedEof");
+
+        let data = make_valid_file(&records);
+        let result = decode_file_with_options(&data, &DecodeOptions::STRICT).unwrap();
+        assert_eq!(result.records[0].as_ref().unwrap().airfield, " EDKA ");
+    }
 }
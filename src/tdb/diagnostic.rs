@@ -0,0 +1,42 @@
+use super::DecodeError;
+use miette::Diagnostic;
+use std::fmt::Display;
+
+/// TDB files are binary, so there are no labels pointing into the file.
+/// The byte offsets are part of the error messages instead.
+impl Diagnostic for DecodeError {
+    fn code<'a>(&'a self) -> Option<Box<dyn Display + 'a>> {
+        let code = match self {
+            DecodeError::Io(_) => return None,
+            DecodeError::UnexpectedEof => "flarmnet::tdb::unexpected_eof",
+            DecodeError::InvalidMagic(_) => "flarmnet::tdb::invalid_magic",
+            DecodeError::UnsortedIndex => "flarmnet::tdb::unsorted_index",
+            DecodeError::InvalidPadding(_) => "flarmnet::tdb::invalid_padding",
+            DecodeError::IndexMismatch { .. } => "flarmnet::tdb::index_mismatch",
+            DecodeError::InvalidFlarmId(_) => "flarmnet::tdb::invalid_flarm_id",
+            DecodeError::InvalidUtf8 { .. } => "flarmnet::tdb::invalid_utf8",
+            DecodeError::InvalidFrequency(error) => return error.code(),
+            DecodeError::Record { source, .. } => return source.code(),
+        };
+        Some(Box::new(code))
+    }
+
+    fn help<'a>(&'a self) -> Option<Box<dyn Display + 'a>> {
+        let help = match self {
+            DecodeError::UnexpectedEof => {
+                "the file is shorter than announced by the record count in the header"
+            }
+            DecodeError::InvalidMagic(_) => "TDB files start with the bytes `08 d5 19 87`",
+            DecodeError::UnsortedIndex => "the FLARM ID index has to be sorted in ascending order",
+            DecodeError::InvalidPadding(_) => "the 8 bytes after the index have to be zero",
+            DecodeError::IndexMismatch { .. } => {
+                "the records have to be in the same order as the FLARM ID index"
+            }
+            DecodeError::InvalidFlarmId(_) => "FLARM IDs range from `000000` to `FFFFFF`",
+            DecodeError::InvalidFrequency(error) => return error.help(),
+            DecodeError::Record { source, .. } => return source.help(),
+            _ => return None,
+        };
+        Some(Box::new(help))
+    }
+}
@@ -1,5 +1,5 @@
 use super::consts::*;
-use crate::{File, Record};
+use crate::{EncodeReport, Extensions, Field, File, Record};
 use std::io::{Cursor, Write};
 use thiserror::Error;
 
@@ -7,16 +7,21 @@ use thiserror::Error;
 pub enum EncodeError {
     #[error(transparent)]
     Io(#[from] std::io::Error),
-    #[error("invalid FLARM id: {0}")]
-    InvalidFlarmId(String),
-    #[error("invalid frequency: {0}")]
-    InvalidFrequency(String),
+    #[error("invalid extension value for {key}: {value}")]
+    InvalidExtension { key: &'static str, value: String },
 }
 
 pub fn encode_file(file: &File) -> Result<Vec<u8>, EncodeError> {
+    let (buffer, _) = encode_file_with_report(file)?;
+    Ok(buffer)
+}
+
+/// Encodes a TDB file and reports the fields that have been truncated to
+/// the maximum string length of the format.
+pub fn encode_file_with_report(file: &File) -> Result<(Vec<u8>, EncodeReport), EncodeError> {
     let mut writer = Writer::new(Cursor::new(Vec::new()));
-    writer.write(file)?;
-    Ok(writer.into_inner().into_inner())
+    let report = writer.write_with_report(file)?;
+    Ok((writer.into_inner().into_inner(), report))
 }
 
 pub struct Writer<W: Write> {
@@ -29,60 +34,119 @@ impl<W: Write> Writer<W> {
     }
 
     pub fn write(&mut self, file: &File) -> Result<(), EncodeError> {
-        let mut entries: Vec<(u32, &Record)> = file
-            .records
-            .iter()
-            .map(|record| Ok((parse_flarm_id(&record.flarm_id)?, record)))
-            .collect::<Result<_, EncodeError>>()?;
+        self.write_with_report(file)?;
+        Ok(())
+    }
 
-        entries.sort_by_key(|(id, _)| *id);
+    /// Writes the file and reports the fields that have been truncated.
+    pub fn write_with_report(&mut self, file: &File) -> Result<EncodeReport, EncodeError> {
+        let mut report = EncodeReport::default();
+        let mut records: Vec<&Record> = file.records.iter().collect();
+        records.sort_by_key(|record| record.flarm_id);
 
-        let count = entries.len() as u32;
+        self.writer.write_all(&encode_header(file, &records)?)?;
+        for record in records {
+            self.writer
+                .write_all(&encode_record(record, &mut report)?)?;
+        }
 
-        // header
-        self.writer.write_all(&MAGIC)?;
-        self.writer.write_all(&file.version.to_le_bytes())?;
-        self.writer.write_all(&count.to_le_bytes())?;
+        Ok(report)
+    }
 
-        // index
-        for (id, _) in &entries {
-            self.writer.write_all(&id.to_le_bytes())?;
-        }
+    pub fn into_inner(self) -> W {
+        self.writer
+    }
+}
 
-        // padding
-        self.writer.write_all(&[0u8; PADDING_SIZE])?;
+/// Encodes the file header, the FLARM ID index and the padding for the
+/// records, which have to be sorted by FLARM ID.
+pub(super) fn encode_header(file: &File, records: &[&Record]) -> Result<Vec<u8>, EncodeError> {
+    let count = records.len() as u32;
+    let mut buf = Vec::with_capacity(HEADER_SIZE + records.len() * INDEX_ENTRY_SIZE + PADDING_SIZE);
 
-        // records
-        for (id, record) in &entries {
-            self.write_record(*id, record)?;
-        }
+    // header
+    buf.extend_from_slice(&MAGIC);
+    buf.extend_from_slice(&file.version.to_le_bytes());
+    buf.extend_from_slice(&count.to_le_bytes());
 
-        Ok(())
+    // index
+    for record in records {
+        buf.extend_from_slice(&record.flarm_id.value().to_le_bytes());
     }
 
-    fn write_record(&mut self, flarm_id: u32, record: &Record) -> Result<(), EncodeError> {
-        let frequency = parse_frequency(&record.frequency)?;
+    // padding
+    let padding = decode_extension::<PADDING_SIZE>(&file.extensions, PADDING_KEY)?;
+    buf.extend_from_slice(&padding);
 
-        let mut buf = [0u8; RECORD_SIZE];
-        buf[FLARM_ID_OFFSET..FLARM_ID_OFFSET + 4].copy_from_slice(&flarm_id.to_le_bytes());
-        buf[FREQUENCY_OFFSET..FREQUENCY_OFFSET + 4].copy_from_slice(&frequency.to_le_bytes());
-        // reserved at offset 8..16 stays zero
-        write_string(&mut buf, CALL_SIGN_OFFSET, &record.call_sign);
-        write_string(&mut buf, PILOT_NAME_OFFSET, &record.pilot_name);
-        write_string(&mut buf, AIRFIELD_OFFSET, &record.airfield);
-        write_string(&mut buf, PLANE_TYPE_OFFSET, &record.plane_type);
-        write_string(&mut buf, REGISTRATION_OFFSET, &record.registration);
+    Ok(buf)
+}
 
-        self.writer.write_all(&buf)?;
-        Ok(())
+pub(super) fn encode_record(
+    record: &Record,
+    report: &mut EncodeReport,
+) -> Result<[u8; RECORD_SIZE], EncodeError> {
+    let frequency = match record.frequency {
+        Some(frequency) => frequency.khz().to_le_bytes(),
+        None => decode_extension::<4>(&record.extensions, FREQUENCY_KEY)?,
+    };
+    let flarm_id = record.flarm_id.value();
+
+    let mut buf = [0u8; RECORD_SIZE];
+    buf[FLARM_ID_OFFSET..FLARM_ID_OFFSET + 4].copy_from_slice(&flarm_id.to_le_bytes());
+    buf[FREQUENCY_OFFSET..FREQUENCY_OFFSET + 4].copy_from_slice(&frequency);
+    let reserved = decode_extension::<RESERVED_SIZE>(&record.extensions, RESERVED_KEY)?;
+    buf[RESERVED_OFFSET..RESERVED_OFFSET + RESERVED_SIZE].copy_from_slice(&reserved);
+
+    let strings = [
+        (CALL_SIGN_OFFSET, Field::CallSign, &record.call_sign),
+        (PILOT_NAME_OFFSET, Field::PilotName, &record.pilot_name),
+        (AIRFIELD_OFFSET, Field::Airfield, &record.airfield),
+        (PLANE_TYPE_OFFSET, Field::PlaneType, &record.plane_type),
+        (
+            REGISTRATION_OFFSET,
+            Field::Registration,
+            &record.registration,
+        ),
+    ];
+    for (offset, field, value) in strings {
+        let written = write_string(&mut buf, offset, value);
+        if written.len() < value.len() {
+            report.truncate(record, field, written);
+        }
     }
 
-    pub fn into_inner(self) -> W {
-        self.writer
+    Ok(buf)
+}
+
+/// Reads the hex encoded bytes of an extension, or zero bytes if the
+/// extension is not set.
+fn decode_extension<const N: usize>(
+    extensions: &Extensions,
+    key: &'static str,
+) -> Result<[u8; N], EncodeError> {
+    let mut bytes = [0u8; N];
+
+    if let Some(value) = extensions.get(key) {
+        let invalid = || EncodeError::InvalidExtension {
+            key,
+            value: value.clone(),
+        };
+
+        if value.len() != N * 2 || !value.is_ascii() {
+            return Err(invalid());
+        }
+
+        for (i, byte) in bytes.iter_mut().enumerate() {
+            *byte = u8::from_str_radix(&value[i * 2..i * 2 + 2], 16).map_err(|_| invalid())?;
+        }
     }
+
+    Ok(bytes)
 }
 
-fn write_string(buf: &mut [u8; RECORD_SIZE], offset: usize, value: &str) {
+/// Writes the string, truncated to the maximum length, and returns the
+/// written part.
+fn write_string<'a>(buf: &mut [u8; RECORD_SIZE], offset: usize, value: &'a str) -> &'a str {
     let max_content = STRING_FIELD_SIZE - 1;
     let truncated = if value.len() > max_content {
         &value[..value.floor_char_boundary(max_content)]
@@ -91,36 +155,21 @@ fn write_string(buf: &mut [u8; RECORD_SIZE], offset: usize, value: &str) {
     };
     buf[offset..offset + truncated.len()].copy_from_slice(truncated.as_bytes());
     // remaining bytes are already zero from initialization
-}
-
-fn parse_flarm_id(s: &str) -> Result<u32, EncodeError> {
-    let id = u32::from_str_radix(s, 16).map_err(|_| EncodeError::InvalidFlarmId(s.to_string()))?;
-    if id > 0xFFFFFF {
-        return Err(EncodeError::InvalidFlarmId(s.to_string()));
-    }
-    Ok(id)
-}
-
-fn parse_frequency(s: &str) -> Result<u32, EncodeError> {
-    if s.is_empty() {
-        return Ok(0);
-    }
-    let mhz: f64 = s
-        .parse()
-        .map_err(|_| EncodeError::InvalidFrequency(s.to_string()))?;
-    Ok((mhz * 1000.0).round() as u32)
+    truncated
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::tdb::decode_file;
+    use crate::DecodeOptions;
+    use crate::tdb::{decode_file, decode_file_with_options};
     use insta::assert_debug_snapshot;
 
     fn make_file(records: Vec<Record>) -> File {
         File {
             version: 1,
             records,
+            extensions: Extensions::new(),
         }
     }
 
@@ -134,13 +183,17 @@ mod tests {
         registration: &str,
     ) -> Record {
         Record {
-            flarm_id: flarm_id.to_string(),
-            frequency: frequency.to_string(),
+            flarm_id: flarm_id.parse().unwrap(),
+            frequency: match frequency {
+                "" => None,
+                frequency => Some(frequency.parse().unwrap()),
+            },
             call_sign: call_sign.to_string(),
             pilot_name: pilot_name.to_string(),
             airfield: airfield.to_string(),
             plane_type: plane_type.to_string(),
             registration: registration.to_string(),
+            extensions: Extensions::new(),
         }
     }
 
@@ -154,8 +207,8 @@ mod tests {
         assert_eq!(decoded.version, 1);
         assert_eq!(decoded.records.len(), 1);
         let record = decoded.records[0].as_ref().unwrap();
-        assert_eq!(record.flarm_id, "3EE3C7");
-        assert_eq!(record.frequency, "123.500");
+        assert_eq!(record.flarm_id.to_string(), "3EE3C7");
+        assert_eq!(record.frequency.unwrap().to_string(), "123.500");
         assert_eq!(record.call_sign, "SG");
         assert_eq!(record.pilot_name, "John Doe");
         assert_eq!(record.airfield, "EDKA");
@@ -163,6 +216,22 @@ mod tests {
         assert_eq!(record.registration, "D-0816");
     }
 
+    #[test]
+    fn encoding_round_trips_padded_values() {
+        let file = make_file(vec![make_record(
+            "3EE3C7",
+            "",
+            " SG",
+            "John Doe ",
+            "",
+            "LS6a",
+            "D-0816 ",
+        )]);
+        let encoded = encode_file(&file).unwrap();
+        let decoded = decode_file(&encoded).unwrap();
+        assert_eq!(decoded.records[0].as_ref().unwrap(), &file.records[0]);
+    }
+
     #[test]
     fn encoding_handles_empty_frequency() {
         let file = make_file(vec![make_record(
@@ -177,7 +246,21 @@ mod tests {
         let encoded = encode_file(&file).unwrap();
         let decoded = decode_file(&encoded).unwrap();
         let record = decoded.records[0].as_ref().unwrap();
-        assert_eq!(record.frequency, "");
+        assert_eq!(record.frequency, None);
+    }
+
+    #[test]
+    fn encoding_writes_back_invalid_frequencies() {
+        let mut record = make_record("000001", "", "", "", "", "", "D-0816");
+        record
+            .extensions
+            .insert(FREQUENCY_KEY.to_string(), "e0a50100".to_string());
+        let encoded = encode_file(&make_file(vec![record.clone()])).unwrap();
+        let offset = HEADER_SIZE + INDEX_ENTRY_SIZE + PADDING_SIZE + FREQUENCY_OFFSET;
+        assert_eq!(encoded[offset..offset + 4], 108000u32.to_le_bytes());
+
+        let decoded = decode_file(&encoded).unwrap();
+        assert_eq!(decoded.records[0].as_ref().unwrap(), &record);
     }
 
     #[test]
@@ -189,10 +272,10 @@ mod tests {
         ]);
         let encoded = encode_file(&file).unwrap();
         let decoded = decode_file(&encoded).unwrap();
-        let ids: Vec<&str> = decoded
+        let ids: Vec<String> = decoded
             .records
             .iter()
-            .map(|r| r.as_ref().unwrap().flarm_id.as_str())
+            .map(|r| r.as_ref().unwrap().flarm_id.to_string())
             .collect();
         assert_eq!(ids, vec!["000000", "000001", "00000F"]);
     }
@@ -233,40 +316,65 @@ mod tests {
     }
 
     #[test]
-    fn encoding_fails_for_invalid_flarm_id() {
-        let file = make_file(vec![make_record("ZZZZZZ", "", "", "", "", "", "")]);
-        assert_debug_snapshot!(
-            encode_file(&file).unwrap_err(),
-            @r###"
-        InvalidFlarmId(
-            "ZZZZZZ",
-        )
-        "###
-        );
+    fn encoding_reports_truncated_fields() {
+        let file = make_file(vec![
+            make_record("000001", "", "", "", "", "Schempp-Hirth Discus", ""),
+            make_record("000000", "", "0123456789ABCDE", "", "", "", ""),
+        ]);
+        let (_, report) = encode_file_with_report(&file).unwrap();
+        assert_debug_snapshot!(report, @r###"
+        EncodeReport {
+            truncated: [
+                TruncatedField {
+                    flarm_id: FlarmId(
+                        000001,
+                    ),
+                    field: PlaneType,
+                    original: "Schempp-Hirth Discus",
+                    written: "Schempp-Hirth D",
+                },
+            ],
+        }
+        "###);
     }
 
     #[test]
-    fn encoding_fails_for_flarm_id_too_large() {
-        let file = make_file(vec![make_record("1000000", "", "", "", "", "", "")]);
-        assert_debug_snapshot!(
-            encode_file(&file).unwrap_err(),
-            @r###"
-        InvalidFlarmId(
-            "1000000",
-        )
-        "###
-        );
+    fn encoding_writes_reserved_and_padding_bytes() {
+        let mut file = make_file(vec![make_record("000001", "", "", "", "", "", "")]);
+        file.extensions
+            .insert(PADDING_KEY.to_string(), "4200000000000000".to_string());
+        file.records[0]
+            .extensions
+            .insert(RESERVED_KEY.to_string(), "01000000000000ff".to_string());
+
+        let encoded = encode_file(&file).unwrap();
+        let padding_offset = HEADER_SIZE + INDEX_ENTRY_SIZE;
+        assert_eq!(encoded[padding_offset], 0x42);
+        let reserved_offset = padding_offset + PADDING_SIZE + RESERVED_OFFSET;
+        assert_eq!(encoded[reserved_offset], 0x01);
+        assert_eq!(encoded[reserved_offset + 7], 0xFF);
+
+        let options = DecodeOptions {
+            accept_non_zero_padding: true,
+            ..Default::default()
+        };
+        let decoded = decode_file_with_options(&encoded, &options).unwrap();
+        assert_eq!(decoded.extensions, file.extensions);
+        assert_eq!(decoded.records[0].as_ref().unwrap(), &file.records[0]);
     }
 
     #[test]
-    fn encoding_fails_for_invalid_frequency() {
-        let file = make_file(vec![make_record("000001", "abc", "", "", "", "", "")]);
+    fn encoding_fails_for_invalid_extension() {
+        let mut file = make_file(vec![]);
+        file.extensions
+            .insert(PADDING_KEY.to_string(), "foo".to_string());
         assert_debug_snapshot!(
             encode_file(&file).unwrap_err(),
             @r###"
-        InvalidFrequency(
-            "abc",
-        )
+        InvalidExtension {
+            key: "tdb.padding",
+            value: "foo",
+        }
         "###
         );
     }
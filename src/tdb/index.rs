@@ -0,0 +1,118 @@
+use super::DecodeError;
+use super::consts::*;
+use super::decode::{check_index_entry, decode_header, decode_padding, decode_record};
+use crate::{DecodeOptions, FlarmId, Record};
+
+/// Zero-copy lookup of single records in a TDB file.
+///
+/// TDB files contain a sorted index of all FLARM IDs in front of the record
+/// data. This type uses that index to find records via binary search,
+/// decoding only the record that is requested. Only the file header, the
+/// index and the padding are validated when the index is created, which makes
+/// this suitable for large memory-mapped files.
+///
+/// # Examples
+///
+/// ```
+/// let data = std::fs::read("tests/fixtures/flarmnet.tdb").unwrap();
+/// let index = flarmnet::tdb::Index::new(&data).unwrap();
+/// assert_eq!(index.version(), 28592);
+/// assert_eq!(index.len(), 3);
+///
+/// let record = index.get("000000".parse().unwrap()).unwrap().unwrap();
+/// assert_eq!(record.registration, "D-2188");
+///
+/// assert!(index.get("ABCDEF".parse().unwrap()).unwrap().is_none());
+/// ```
+#[derive(Debug, Clone, Copy)]
+pub struct Index<'a> {
+    version: u32,
+    flarm_ids: &'a [[u8; INDEX_ENTRY_SIZE]],
+    records: &'a [[u8; RECORD_SIZE]],
+    options: DecodeOptions,
+}
+
+impl<'a> Index<'a> {
+    /// Creates a new index for the given TDB file content.
+    ///
+    /// Returns an error if the header is invalid, if the FLARM ID index is
+    /// not sorted or if the file is too short for the number of records in
+    /// the header.
+    pub fn new(data: &'a [u8]) -> Result<Self, DecodeError> {
+        Self::with_options(data, &DecodeOptions::default())
+    }
+
+    /// Creates a new index with the given [DecodeOptions], which are also
+    /// used to decode the records.
+    ///
+    /// Since the binary search relies on the sorted FLARM ID index,
+    /// [accept_unsorted_index](DecodeOptions::accept_unsorted_index) is
+    /// ignored.
+    pub fn with_options(data: &'a [u8], options: &DecodeOptions) -> Result<Self, DecodeError> {
+        let (header, data) = data
+            .split_first_chunk::<HEADER_SIZE>()
+            .ok_or(DecodeError::UnexpectedEof)?;
+        let (version, record_count) = decode_header(header)?;
+
+        // the record count is read from the file and can't be trusted, so a
+        // count that overflows the index size can only be a truncated file
+        let (index, data) = record_count
+            .checked_mul(INDEX_ENTRY_SIZE)
+            .and_then(|index_size| data.split_at_checked(index_size))
+            .ok_or(DecodeError::UnexpectedEof)?;
+        let (flarm_ids, _) = index.as_chunks::<INDEX_ENTRY_SIZE>();
+        if !flarm_ids.is_sorted_by_key(|entry| u32::from_le_bytes(*entry)) {
+            return Err(DecodeError::UnsortedIndex);
+        }
+
+        let (padding, data) = data
+            .split_first_chunk::<PADDING_SIZE>()
+            .ok_or(DecodeError::UnexpectedEof)?;
+        decode_padding(padding, options)?;
+
+        let (records, _) = data.as_chunks::<RECORD_SIZE>();
+        let records = records
+            .get(..record_count)
+            .ok_or(DecodeError::UnexpectedEof)?;
+
+        Ok(Self {
+            version,
+            flarm_ids,
+            records,
+            options: *options,
+        })
+    }
+
+    /// Returns the file version.
+    pub fn version(&self) -> u32 {
+        self.version
+    }
+
+    /// Returns the number of records in the file.
+    pub fn len(&self) -> usize {
+        self.records.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.records.is_empty()
+    }
+
+    /// Looks up and decodes the record with the given FLARM ID.
+    ///
+    /// Returns `Ok(None)` if the FLARM ID is not part of the index, and
+    /// [DecodeError::IndexMismatch] if the record found via the index has a
+    /// different FLARM ID.
+    pub fn get(&self, flarm_id: FlarmId) -> Result<Option<Record>, DecodeError> {
+        let position = self
+            .flarm_ids
+            .binary_search_by_key(&flarm_id.value(), |entry| u32::from_le_bytes(*entry));
+
+        let Ok(position) = position else {
+            return Ok(None);
+        };
+
+        let expected = u32::from_le_bytes(self.flarm_ids[position]);
+        let record = decode_record(&self.records[position], &self.options)?;
+        check_index_entry(record, expected).map(Some)
+    }
+}
@@ -1,11 +1,76 @@
 //! Decoder/Encoder for Air Avionics TDB file format.
 //!
-//! The [decode_file] function can be used to decode FlarmNet files in
-//! Air Avionics TDB format.
+//! The [decode_file] and [decode_reader] functions can be used to decode
+//! FlarmNet files in Air Avionics TDB format. The [Reader] can be used to
+//! decode the records one at a time without holding the whole file in memory,
+//! and the [Index] looks up single records without decoding the whole file.
+//!
+//! ## Extensions
+//!
+//! The decoder stores the following [Extensions](crate::Extensions) as hex
+//! strings if they contain any non-zero bytes, and the encoder writes them
+//! back:
+//!
+//! - `tdb.frequency` (record): the 4 frequency bytes if they are not a valid
+//!   [Frequency](crate::Frequency), see
+//!   [accept_invalid_frequencies](crate::DecodeOptions::accept_invalid_frequencies).
+//!   The encoder only writes them if the record has no frequency.
+//! - `tdb.padding` (file): the 8 padding bytes between index and records, see
+//!   [accept_non_zero_padding](crate::DecodeOptions::accept_non_zero_padding)
+//! - `tdb.reserved` (record): the 8 reserved bytes after the frequency
+//!
+//! ## Validation
+//!
+//! Records that do not match their FLARM ID index entry are reported as
+//! [DecodeError::IndexMismatch]. The decoder also checks that the index is
+//! sorted and that the padding between index and records only contains zero
+//! bytes, and fails for the whole file otherwise, unless
+//! [accept_unsorted_index](crate::DecodeOptions::accept_unsorted_index) or
+//! [accept_non_zero_padding](crate::DecodeOptions::accept_non_zero_padding)
+//! are set, e.g. with [DecodeOptions::LENIENT](crate::DecodeOptions::LENIENT).
 
-mod consts;
+#[cfg(feature = "async")]
+mod async_io;
+pub(crate) mod consts;
 mod decode;
+#[cfg(feature = "miette")]
+mod diagnostic;
 mod encode;
+mod index;
 
+#[cfg(feature = "async")]
+pub use async_io::*;
 pub use decode::*;
 pub use encode::*;
+pub use index::Index;
+
+/// [Codec](crate::Codec) implementation for the Air Avionics TDB file format.
+#[derive(Debug, Clone, Copy)]
+pub struct Tdb;
+
+impl crate::Codec for Tdb {
+    const FORMAT: crate::Format = crate::Format::Tdb;
+
+    fn decode_with_options(
+        data: &[u8],
+        options: &crate::DecodeOptions,
+    ) -> Result<crate::DecodedFile, crate::Error> {
+        let decoded = decode_file_with_options(data, options)?;
+        Ok(crate::DecodedFile::new(
+            Self::FORMAT,
+            decoded.version,
+            decoded.records,
+            decoded.extensions,
+        ))
+    }
+
+    fn encode(file: &crate::File) -> Result<Vec<u8>, crate::Error> {
+        Ok(encode_file(file)?)
+    }
+
+    fn encode_with_report(
+        file: &crate::File,
+    ) -> Result<(Vec<u8>, crate::EncodeReport), crate::Error> {
+        Ok(encode_file_with_report(file)?)
+    }
+}
@@ -0,0 +1,27 @@
+//! Fixtures shared by the unit tests of the format independent modules.
+
+use crate::{File, Record};
+
+/// Creates a record with the given FLARM ID, registration and call sign and
+/// empty other fields.
+pub(crate) fn make_record(flarm_id: &str, registration: &str, call_sign: &str) -> Record {
+    Record {
+        flarm_id: flarm_id.parse().unwrap(),
+        pilot_name: String::new(),
+        airfield: String::new(),
+        plane_type: String::new(),
+        registration: registration.to_string(),
+        call_sign: call_sign.to_string(),
+        frequency: None,
+        extensions: Default::default(),
+    }
+}
+
+/// Creates a file with the given version and records and no extensions.
+pub(crate) fn make_file(version: u32, records: Vec<Record>) -> File {
+    File {
+        version,
+        records,
+        extensions: Default::default(),
+    }
+}
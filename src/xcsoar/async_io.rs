@@ -0,0 +1,167 @@
+use super::decode::ReaderState;
+use super::encode::{encode_record, encode_version, line_ending, original_line};
+use super::{DecodeError, DecodedFile, EncodeError};
+use crate::{DecodeOptions, EncodeReport, Extensions, File, Record};
+use tokio::io::{AsyncBufRead, AsyncBufReadExt, AsyncRead, AsyncWrite, AsyncWriteExt, BufReader};
+
+/// Decodes a FlarmNet file from any [AsyncRead] implementation.
+///
+/// The records are decoded line by line, see [AsyncReader].
+///
+/// # Examples
+///
+/// ```
+/// # #[tokio::main(flavor = "current_thread")]
+/// # async fn main() {
+/// let file = tokio::fs::File::open("tests/fixtures/data.fln").await.unwrap();
+/// let result = flarmnet::xcsoar::decode_async_reader(file).await.unwrap();
+/// assert_eq!(result.version, 28592);
+/// # }
+/// ```
+pub async fn decode_async_reader<R: AsyncRead + Unpin>(
+    reader: R,
+) -> Result<DecodedFile, DecodeError> {
+    decode_async_reader_with_options(reader, &DecodeOptions::default()).await
+}
+
+/// Decodes a FlarmNet file from any [AsyncRead] implementation with the
+/// given [DecodeOptions].
+pub async fn decode_async_reader_with_options<R: AsyncRead + Unpin>(
+    reader: R,
+    options: &DecodeOptions,
+) -> Result<DecodedFile, DecodeError> {
+    let mut reader = AsyncReader::with_options(BufReader::new(reader), options).await?;
+    let mut records = Vec::new();
+    while let Some(result) = reader.next().await {
+        records.push(result);
+    }
+
+    Ok(DecodedFile {
+        version: reader.state.version,
+        records,
+        extensions: reader.state.extensions,
+    })
+}
+
+/// Async version of [Reader](super::Reader) for [AsyncBufRead]
+/// implementations.
+///
+/// The records are read and decoded one line at a time by
+/// [AsyncReader::next].
+///
+/// # Examples
+///
+/// ```
+/// # #[tokio::main(flavor = "current_thread")]
+/// # async fn main() {
+/// let file = tokio::fs::File::open("tests/fixtures/data.fln").await.unwrap();
+/// let mut reader = flarmnet::xcsoar::AsyncReader::new(tokio::io::BufReader::new(file))
+///     .await
+///     .unwrap();
+/// assert_eq!(reader.version(), 28592);
+///
+/// while let Some(result) = reader.next().await {
+///     let record = result.unwrap();
+///     println!("{}: {}", record.flarm_id, record.registration);
+/// }
+/// # }
+/// ```
+pub struct AsyncReader<R: AsyncBufRead + Unpin> {
+    inner: R,
+    buf: Vec<u8>,
+    state: ReaderState,
+}
+
+impl<R: AsyncBufRead + Unpin> AsyncReader<R> {
+    /// Creates a new reader and reads the file version from the first line.
+    pub async fn new(inner: R) -> Result<Self, DecodeError> {
+        Self::with_options(inner, &DecodeOptions::default()).await
+    }
+
+    /// Creates a new reader with the given [DecodeOptions], see
+    /// [Reader::with_options](super::Reader::with_options).
+    pub async fn with_options(mut inner: R, options: &DecodeOptions) -> Result<Self, DecodeError> {
+        let mut buf = Vec::new();
+        inner.read_until(b'\n', &mut buf).await?;
+        let state = ReaderState::new(&buf, options)?;
+        Ok(Self { inner, buf, state })
+    }
+
+    /// Returns the file version.
+    pub fn version(&self) -> u32 {
+        self.state.version
+    }
+
+    /// Returns the file-level [Extensions], see [module documentation](super).
+    pub fn extensions(&self) -> &Extensions {
+        &self.state.extensions
+    }
+
+    /// Reads and decodes the next record, or returns `None` at the end of
+    /// the file.
+    pub async fn next(&mut self) -> Option<Result<Record, DecodeError>> {
+        loop {
+            if self.state.prepare_line(&mut self.buf) {
+                match self.inner.read_until(b'\n', &mut self.buf).await {
+                    Ok(0) => return None,
+                    Ok(_) => {}
+                    Err(error) => return Some(Err(error.into())),
+                }
+            }
+
+            if let Some(result) = self.state.decode_line(&self.buf) {
+                return Some(result);
+            }
+        }
+    }
+
+    pub fn into_inner(self) -> R {
+        self.inner
+    }
+}
+
+/// Async version of [Writer](super::Writer) for [AsyncWrite] implementations.
+///
+/// The records are encoded one at a time, and the inner writer is flushed
+/// after the whole file has been written.
+pub struct AsyncWriter<W: AsyncWrite + Unpin> {
+    writer: W,
+}
+
+impl<W: AsyncWrite + Unpin> AsyncWriter<W> {
+    pub fn new(inner: W) -> Self {
+        Self { writer: inner }
+    }
+
+    pub async fn write(&mut self, file: &File) -> Result<(), EncodeError> {
+        self.write_with_report(file).await?;
+        Ok(())
+    }
+
+    /// Writes the file and reports the fields that have been truncated.
+    pub async fn write_with_report(&mut self, file: &File) -> Result<EncodeReport, EncodeError> {
+        let mut report = EncodeReport::default();
+        let line_ending = line_ending(file);
+
+        self.writer
+            .write_all(encode_version(file).as_bytes())
+            .await?;
+        self.writer.write_all(line_ending.as_bytes()).await?;
+
+        for record in &file.records {
+            let line = match original_line(record) {
+                Some(line) => line.to_string(),
+                None => encode_record(record, &mut report)?,
+            };
+            self.writer.write_all(line.as_bytes()).await?;
+            self.writer.write_all(line_ending.as_bytes()).await?;
+        }
+        self.writer.flush().await?;
+
+        Ok(report)
+    }
+
+    pub fn into_inner(self) -> W {
+        self.writer
+    }
+}
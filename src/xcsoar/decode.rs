@@ -1,10 +1,16 @@
+use super::encode::encode_record;
 use super::fields::*;
-use crate::Record;
+use super::{FREQUENCY_KEY, LINE_ENDING_KEY, LINE_KEY, VERSION_KEY};
+use crate::{DecodeOptions, EncodeReport, Extensions, Frequency, ParseFrequencyError, Record};
 use encoding_rs::mem::decode_latin1;
+use std::io::{BufRead, BufReader, Read};
+use std::ops::Range;
 use thiserror::Error;
 
 #[derive(Error, Debug)]
 pub enum DecodeError {
+    #[error(transparent)]
+    Io(#[from] std::io::Error),
     #[error("missing file version")]
     MissingVersion,
     #[error("invalid file version: {0}")]
@@ -15,12 +21,25 @@ pub enum DecodeError {
     UnexpectedCharacter(String),
     #[error("invalid FLARM id: {0}")]
     InvalidFlarmId(String),
+    #[error(transparent)]
+    InvalidFrequency(#[from] ParseFrequencyError),
+    /// A record could not be decoded. `index` is the zero-based index of the
+    /// record, `line` the one-based line number in the file and `span` the
+    /// byte range of the invalid characters in the file.
+    #[error("invalid record in line {line}")]
+    Record {
+        index: usize,
+        line: usize,
+        span: Range<usize>,
+        source: Box<DecodeError>,
+    },
 }
 
 #[derive(Debug)]
 pub struct DecodedFile {
     pub version: u32,
     pub records: Vec<Result<Record, DecodeError>>,
+    pub extensions: Extensions,
 }
 
 /// Decodes a FlarmNet file.
@@ -40,58 +59,336 @@ pub struct DecodedFile {
 /// assert_eq!(result.records.iter().filter(|it| it.is_ok()).count(), 3);
 /// ```
 pub fn decode_file(file: &str) -> Result<DecodedFile, DecodeError> {
-    let mut lines = file.lines();
+    decode_file_with_options(file, &DecodeOptions::default())
+}
+
+/// Decodes a FlarmNet file with the given [DecodeOptions].
+pub fn decode_file_with_options(
+    file: &str,
+    options: &DecodeOptions,
+) -> Result<DecodedFile, DecodeError> {
+    decode_buf_reader(file.as_bytes(), options)
+}
+
+/// Decodes a FlarmNet file from any [Read] implementation.
+///
+/// The records are decoded line by line, so this is preferable over reading
+/// the whole file into a string first. See [Reader] for a way to avoid
+/// collecting the records, too.
+///
+/// # Examples
+///
+/// ```
+/// let file = std::fs::File::open("tests/fixtures/data.fln").unwrap();
+/// let result = flarmnet::xcsoar::decode_reader(file).unwrap();
+/// assert_eq!(result.version, 28592);
+/// assert_eq!(result.records.len(), 3);
+/// ```
+pub fn decode_reader<R: Read>(reader: R) -> Result<DecodedFile, DecodeError> {
+    decode_reader_with_options(reader, &DecodeOptions::default())
+}
+
+/// Decodes a FlarmNet file from any [Read] implementation with the given
+/// [DecodeOptions].
+pub fn decode_reader_with_options<R: Read>(
+    reader: R,
+    options: &DecodeOptions,
+) -> Result<DecodedFile, DecodeError> {
+    decode_buf_reader(BufReader::new(reader), options)
+}
+
+fn decode_buf_reader<R: BufRead>(
+    reader: R,
+    options: &DecodeOptions,
+) -> Result<DecodedFile, DecodeError> {
+    let reader = Reader::with_options(reader, options)?;
+    let version = reader.version();
+    let extensions = reader.extensions().clone();
+    let records = reader.collect();
+
+    Ok(DecodedFile {
+        version,
+        records,
+        extensions,
+    })
+}
+
+/// Streaming decoder for FlarmNet files.
+///
+/// The file version is read when the reader is created, while the records
+/// are decoded one at a time when iterating over the reader.
+///
+/// # Examples
+///
+/// ```
+/// let file = std::fs::File::open("tests/fixtures/data.fln").unwrap();
+/// let reader = flarmnet::xcsoar::Reader::new(std::io::BufReader::new(file)).unwrap();
+/// assert_eq!(reader.version(), 28592);
+///
+/// for result in reader {
+///     let record = result.unwrap();
+///     println!("{}: {}", record.flarm_id, record.registration);
+/// }
+/// ```
+pub struct Reader<R: BufRead> {
+    inner: R,
+    buf: Vec<u8>,
+    state: ReaderState,
+}
+
+impl<R: BufRead> Reader<R> {
+    /// Creates a new reader and reads the file version from the first line.
+    pub fn new(inner: R) -> Result<Self, DecodeError> {
+        Self::with_options(inner, &DecodeOptions::default())
+    }
+
+    /// Creates a new reader with the given [DecodeOptions] and reads the file
+    /// version from the first line.
+    ///
+    /// If [accept_missing_version](DecodeOptions::accept_missing_version)
+    /// is set and the first line is not a valid file version, version `0` is
+    /// used and the first line is decoded as a record instead.
+    pub fn with_options(mut inner: R, options: &DecodeOptions) -> Result<Self, DecodeError> {
+        let mut buf = Vec::new();
+        inner.read_until(b'\n', &mut buf)?;
+        let state = ReaderState::new(&buf, options)?;
+        Ok(Self { inner, buf, state })
+    }
+
+    /// Returns the file version.
+    pub fn version(&self) -> u32 {
+        self.state.version
+    }
+
+    /// Returns the file-level [Extensions], see [module documentation](super).
+    pub fn extensions(&self) -> &Extensions {
+        &self.state.extensions
+    }
+
+    pub fn into_inner(self) -> R {
+        self.inner
+    }
+}
+
+impl<R: BufRead> Iterator for Reader<R> {
+    type Item = Result<Record, DecodeError>;
 
-    let version = lines.next().ok_or(DecodeError::MissingVersion)?;
-    let version = u32::from_str_radix(version, 16)
-        .map_err(|_| DecodeError::InvalidVersion(version.to_string()))?;
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if self.state.prepare_line(&mut self.buf) {
+                match self.inner.read_until(b'\n', &mut self.buf) {
+                    Ok(0) => return None,
+                    Ok(_) => {}
+                    Err(error) => return Some(Err(error.into())),
+                }
+            }
 
-    let records = lines
-        .filter(|it| !it.is_empty())
-        .map(decode_record)
-        .collect();
+            if let Some(result) = self.state.decode_line(&self.buf) {
+                return Some(result);
+            }
+        }
+    }
+}
 
-    Ok(DecodedFile { version, records })
+/// File version and position of a [Reader] or
+/// [AsyncReader](super::AsyncReader), which only differ in how the lines
+/// are read.
+pub(super) struct ReaderState {
+    pub(super) version: u32,
+    pub(super) extensions: Extensions,
+    options: DecodeOptions,
+    /// Whether the first line is a record line that has not been decoded yet.
+    pending: bool,
+    /// Number of lines that have been read so far.
+    line: usize,
+    /// Byte offset of the current line.
+    offset: usize,
+    /// Number of records that have been returned so far.
+    index: usize,
+}
+
+impl ReaderState {
+    /// Decodes the file version from the first line, which is empty if the
+    /// file is empty.
+    pub(super) fn new(first_line: &[u8], options: &DecodeOptions) -> Result<Self, DecodeError> {
+        let mut extensions = Extensions::new();
+
+        let line = match first_line.is_empty() {
+            true if !options.accept_missing_version => return Err(DecodeError::MissingVersion),
+            true => 0,
+            false => 1,
+        };
+        if first_line.ends_with(b"\r\n") {
+            extensions.insert(LINE_ENDING_KEY.to_string(), "\r\n".to_string());
+        }
+
+        let version_line = String::from_utf8_lossy(trim_line_ending(first_line)).into_owned();
+        let (version, pending) = match u32::from_str_radix(&version_line, 16) {
+            Ok(version) => (version, false),
+            Err(_) if options.accept_missing_version => (0, !version_line.is_empty()),
+            Err(_) => return Err(DecodeError::InvalidVersion(version_line)),
+        };
+
+        if !pending && !first_line.is_empty() && format!("{version:06x}") != version_line {
+            extensions.insert(VERSION_KEY.to_string(), version_line);
+        }
+
+        Ok(Self {
+            version,
+            extensions,
+            options: *options,
+            pending,
+            line,
+            offset: 0,
+            index: 0,
+        })
+    }
+
+    /// Clears `buf` for reading the next line into it, unless it contains a
+    /// first line that still has to be decoded.
+    ///
+    /// Returns `true` if the next line has to be read.
+    pub(super) fn prepare_line(&mut self, buf: &mut Vec<u8>) -> bool {
+        if std::mem::take(&mut self.pending) {
+            return false;
+        }
+
+        self.offset += buf.len();
+        self.line += 1;
+        buf.clear();
+        true
+    }
+
+    /// Decodes the line in `buf`, or returns `None` for empty lines.
+    pub(super) fn decode_line(&mut self, buf: &[u8]) -> Option<Result<Record, DecodeError>> {
+        let line = trim_line_ending(buf);
+        if line.is_empty() {
+            return None;
+        }
+
+        // the format only consists of ASCII characters, so any invalid UTF-8
+        // will be reported as unexpected characters
+        let line = String::from_utf8_lossy(line);
+        let index = self.index;
+        self.index += 1;
+        let result = decode_record_with_options(&line, &self.options);
+        Some(result.map_err(|error| {
+            let span = error_span(&line, &error);
+            DecodeError::Record {
+                index,
+                line: self.line,
+                span: self.offset + span.start..self.offset + span.end,
+                source: Box::new(error),
+            }
+        }))
+    }
+}
+
+/// Returns the range of the characters within `line` that caused the error.
+fn error_span(line: &str, error: &DecodeError) -> Range<usize> {
+    let clamp = |range: Range<usize>| range.start.min(line.len())..range.end.min(line.len());
+    match error {
+        DecodeError::InvalidFlarmId(_) => clamp(FLARM_ID_RANGE),
+        DecodeError::InvalidFrequency(_) => clamp(FREQUENCY_RANGE),
+        // the fields are decoded in order, so the first invalid pair of
+        // characters is the one that has been reported
+        DecodeError::UnexpectedCharacter(_) => line
+            .as_bytes()
+            .chunks(2)
+            .position(|pair| {
+                let pair = std::str::from_utf8(pair).ok();
+                pair.and_then(|pair| u8::from_str_radix(pair, 16).ok())
+                    .is_none()
+            })
+            .map(|i| clamp(i * 2..i * 2 + 2))
+            .unwrap_or(0..line.len()),
+        _ => 0..line.len(),
+    }
+}
+
+fn trim_line_ending(line: &[u8]) -> &[u8] {
+    let line = line.strip_suffix(b"\n").unwrap_or(line);
+    line.strip_suffix(b"\r").unwrap_or(line)
 }
 
 /// Decodes a single FlarmNet file record.
 ///
+/// If encoding the record would not result in the same line again (e.g.
+/// because of leading whitespace or uppercase hex digits), the original line
+/// is stored in the record extensions.
+///
 /// # Examples
 ///
 /// ```
 /// # use flarmnet::Record;
-/// let line = "334545334337546f62696173204269656e69656b2020202020202045444b4120202020202020202020202020202020204c5336612020202020202020202020202020202020442d30383136205347203133302e353330";
+/// let line = "336565336337546f62696173204269656e69656b2020202020202045444b4120202020202020202020202020202020204c5336612020202020202020202020202020202020442d30383136205347203133302e353330";
 ///
 /// let result = flarmnet::xcsoar::decode_record(line);
 /// assert_eq!(result.unwrap(), Record {
-///     flarm_id: "3EE3C7".to_string(),
+///     flarm_id: "3EE3C7".parse().unwrap(),
 ///     pilot_name: "Tobias Bieniek".to_string(),
 ///     airfield: "EDKA".to_string(),
 ///     plane_type: "LS6a".to_string(),
 ///     registration: "D-0816".to_string(),
 ///     call_sign: "SG".to_string(),
-///     frequency: "130.530".to_string(),
+///     frequency: Some("130.530".parse().unwrap()),
+///     extensions: Default::default(),
 /// });
 /// ```
 pub fn decode_record(line: &str) -> Result<Record, DecodeError> {
+    decode_record_with_options(line, &DecodeOptions::default())
+}
+
+/// Decodes a single FlarmNet file record with the given [DecodeOptions], see
+/// [decode_record].
+pub fn decode_record_with_options(
+    line: &str,
+    options: &DecodeOptions,
+) -> Result<Record, DecodeError> {
     let line_length = line.len();
-    if line_length != LINE_LENGTH {
+    let is_short = line_length < LINE_LENGTH && line_length.is_multiple_of(2);
+    if line_length != LINE_LENGTH && !(is_short && options.accept_short_lines) {
         return Err(DecodeError::UnexpectedLineLength(line_length));
     }
 
-    let flarm_id = decode_str(&line[FLARM_ID_RANGE])?;
-    if u32::from_str_radix(&flarm_id, 16).is_err() {
+    // missing characters are treated as space padding
+    let padded;
+    let line = match line_length < LINE_LENGTH {
+        true => {
+            padded = format!("{line}{}", "20".repeat((LINE_LENGTH - line_length) / 2));
+            padded.as_str()
+        }
+        false => line,
+    };
+
+    let decode_str = |range| decode_str(&line[range]);
+
+    let flarm_id = decode_str(FLARM_ID_RANGE)?;
+    if !options.accept_non_canonical_ids && !is_canonical_id(&flarm_id) {
         return Err(DecodeError::InvalidFlarmId(flarm_id));
     }
+    let flarm_id = flarm_id
+        .parse()
+        .map_err(|_| DecodeError::InvalidFlarmId(flarm_id))?;
 
-    let pilot_name = decode_str(&line[PILOT_NAME_RANGE])?;
-    let airfield = decode_str(&line[AIRFIELD_RANGE])?;
-    let plane_type = decode_str(&line[PLANE_TYPE_RANGE])?;
-    let registration = decode_str(&line[REGISTRATION_RANGE])?;
-    let call_sign = decode_str(&line[CALL_SIGN_RANGE])?;
-    let frequency = decode_str(&line[FREQUENCY_RANGE])?;
+    let pilot_name = decode_str(PILOT_NAME_RANGE)?;
+    let airfield = decode_str(AIRFIELD_RANGE)?;
+    let plane_type = decode_str(PLANE_TYPE_RANGE)?;
+    let registration = decode_str(REGISTRATION_RANGE)?;
+    let call_sign = decode_str(CALL_SIGN_RANGE)?;
+    let frequency = decode_str(FREQUENCY_RANGE)?;
+    let mut extensions = Extensions::new();
+    let frequency = match frequency.parse::<Frequency>() {
+        _ if frequency.is_empty() => None,
+        Ok(frequency) => Some(frequency),
+        Err(_) if options.accept_invalid_frequencies => {
+            extensions.insert(FREQUENCY_KEY.to_string(), frequency);
+            None
+        }
+        Err(error) => return Err(error.into()),
+    };
 
-    Ok(Record {
+    let mut record = Record {
         flarm_id,
         pilot_name,
         airfield,
@@ -99,7 +396,28 @@ pub fn decode_record(line: &str) -> Result<Record, DecodeError> {
         registration,
         call_sign,
         frequency,
-    })
+        extensions,
+    };
+
+    if encode_record(&record, &mut EncodeReport::default())
+        .ok()
+        .as_deref()
+        != Some(line)
+    {
+        record
+            .extensions
+            .insert(LINE_KEY.to_string(), line.to_string());
+    }
+
+    Ok(record)
+}
+
+/// Checks whether the FLARM ID consists of six lowercase hex digits.
+fn is_canonical_id(flarm_id: &str) -> bool {
+    flarm_id.len() == FLARM_ID_LENGTH
+        && flarm_id
+            .bytes()
+            .all(|b| b.is_ascii_digit() || (b'a'..=b'f').contains(&b))
 }
 
 fn decode_str(value: &str) -> Result<String, DecodeError> {
@@ -116,7 +434,9 @@ fn decode_str(value: &str) -> Result<String, DecodeError> {
 
 #[cfg(test)]
 mod tests {
-    use super::{decode_file, decode_record};
+    use super::{decode_file, decode_file_with_options, decode_record, decode_record_with_options};
+    use crate::DecodeOptions;
+    use crate::xcsoar::FREQUENCY_KEY;
     use insta::assert_debug_snapshot;
 
     #[test]
@@ -202,4 +522,106 @@ mod tests {
         "###
         );
     }
+
+    #[test]
+    fn decoding_keeps_records_with_invalid_frequency() {
+        let row = "3030303030304dfc6c6c6572202020202020202020202020202020442d3231383820202020202020202020202020202041534b2d3133202020202020202020202020202020442d32313838202020203132332e353230";
+        let record = decode_record(row).unwrap();
+        assert_eq!(record.registration, "D-2188");
+        assert_eq!(record.frequency, None);
+        assert_eq!(record.extensions[FREQUENCY_KEY], "123.520");
+
+        assert_debug_snapshot!(
+            decode_record_with_options(row, &DecodeOptions::STRICT).unwrap_err(),
+            @r###"
+        InvalidFrequency(
+            InvalidChannel(
+                "123.520",
+            ),
+        )
+        "###
+        );
+    }
+
+    #[test]
+    fn decoding_keeps_non_canonical_lines() {
+        // uppercase hex digits and leading whitespace in the pilot name
+        let row = "303030303046202020202020202020202020202020202020202020204D5950524F474F202020202020202020202020202020202020202020202020202020202020202020202020202020202020202020202020202020";
+        let record = decode_record(row).unwrap();
+        assert_eq!(record.flarm_id.to_string(), "00000F");
+        assert_eq!(record.airfield, "MYPROGO");
+        assert_eq!(record.extensions.get("xcsoar.line").unwrap(), row);
+
+        let row = "3030303030304dfc6c6c6572202020202020202020202020202020442d3231383820202020202020202020202020202041534b2d3133202020202020202020202020202020442d32313838202020203132332e313530";
+        let record = decode_record(row).unwrap();
+        assert!(record.extensions.is_empty());
+    }
+
+    #[test]
+    fn decoding_keeps_version_line_and_line_endings() {
+        let file = "006FB0\r\n";
+        assert_debug_snapshot!(decode_file(file).unwrap().extensions, @r###"
+        {
+            "xcsoar.line_ending": "\r\n",
+            "xcsoar.version": "006FB0",
+        }
+        "###);
+    }
+
+    #[test]
+    fn decoding_reports_record_locations() {
+        // empty lines are skipped, but still counted
+        let file = "006fb0\n\nfoo\n";
+        assert_debug_snapshot!(decode_file(file).unwrap().records, @r###"
+        [
+            Err(
+                Record {
+                    index: 0,
+                    line: 3,
+                    span: 8..11,
+                    source: UnexpectedLineLength(
+                        3,
+                    ),
+                },
+            ),
+        ]
+        "###);
+    }
+
+    #[test]
+    fn strict_decoding_rejects_non_canonical_input() {
+        // uppercase FLARM ID and leading whitespace in the airfield
+        let row = "303030303046202020202020202020202020202020202020202020204D5950524F474F202020202020202020202020202020202020202020202020202020202020202020202020202020202020202020202020202020";
+        assert_debug_snapshot!(
+            decode_record_with_options(row, &DecodeOptions::STRICT).unwrap_err(),
+            @r###"
+        InvalidFlarmId(
+            "00000F",
+        )
+        "###
+        );
+
+        let options = DecodeOptions {
+            accept_non_canonical_ids: true,
+            ..DecodeOptions::STRICT
+        };
+        // the padding of the fields is always removed
+        let record = decode_record_with_options(row, &options).unwrap();
+        assert_eq!(record.airfield, "MYPROGO");
+    }
+
+    #[test]
+    fn lenient_decoding_accepts_missing_version() {
+        let row = "3030303030304dfc6c6c6572202020202020202020202020202020442d3231383820202020202020202020202020202041534b2d3133202020202020202020202020202020442d32313838202020203132332e313530";
+        let file = format!("{row}\n");
+        let result = decode_file_with_options(&file, &DecodeOptions::LENIENT).unwrap();
+        assert_eq!(result.version, 0);
+        assert_eq!(result.records.len(), 1);
+        assert_eq!(result.records[0].as_ref().unwrap().pilot_name, "Müller");
+        assert!(result.extensions.is_empty());
+
+        let result = decode_file_with_options("", &DecodeOptions::LENIENT).unwrap();
+        assert_eq!(result.version, 0);
+        assert!(result.records.is_empty());
+    }
 }
@@ -0,0 +1,61 @@
+use super::DecodeError;
+use super::fields::LINE_LENGTH;
+use miette::{Diagnostic, LabeledSpan};
+use std::fmt::Display;
+
+/// The labels refer to the whole file, so the file content has to be
+/// attached via [miette::Report::with_source_code] to render them.
+impl Diagnostic for DecodeError {
+    fn code<'a>(&'a self) -> Option<Box<dyn Display + 'a>> {
+        let code = match self {
+            DecodeError::Io(_) => return None,
+            DecodeError::MissingVersion => "flarmnet::xcsoar::missing_version",
+            DecodeError::InvalidVersion(_) => "flarmnet::xcsoar::invalid_version",
+            DecodeError::UnexpectedLineLength(_) => "flarmnet::xcsoar::unexpected_line_length",
+            DecodeError::UnexpectedCharacter(_) => "flarmnet::xcsoar::unexpected_character",
+            DecodeError::InvalidFlarmId(_) => "flarmnet::xcsoar::invalid_flarm_id",
+            DecodeError::InvalidFrequency(error) => return error.code(),
+            DecodeError::Record { source, .. } => return source.code(),
+        };
+        Some(Box::new(code))
+    }
+
+    fn help<'a>(&'a self) -> Option<Box<dyn Display + 'a>> {
+        let help = match self {
+            DecodeError::Io(_) => return None,
+            DecodeError::MissingVersion | DecodeError::InvalidVersion(_) => {
+                "the first line contains the file version as hex number, e.g. `006fb0`".to_string()
+            }
+            DecodeError::UnexpectedLineLength(_) => {
+                format!("each record consists of {LINE_LENGTH} hex digits")
+            }
+            DecodeError::UnexpectedCharacter(_) => {
+                "each character is encoded as two hex digits of its latin1 code".to_string()
+            }
+            DecodeError::InvalidFlarmId(_) => {
+                "FLARM IDs consist of six hex digits, e.g. `3ee3c7`".to_string()
+            }
+            DecodeError::InvalidFrequency(error) => return error.help(),
+            DecodeError::Record { source, .. } => return source.help(),
+        };
+        Some(Box::new(help))
+    }
+
+    fn labels(&self) -> Option<Box<dyn Iterator<Item = LabeledSpan> + '_>> {
+        let DecodeError::Record { span, source, .. } = self else {
+            return None;
+        };
+
+        let label = match **source {
+            DecodeError::UnexpectedLineLength(length) => {
+                format!("{length} instead of {LINE_LENGTH} characters")
+            }
+            DecodeError::UnexpectedCharacter(_) => "not a hex-encoded character".to_string(),
+            DecodeError::InvalidFlarmId(_) => "FLARM ID is not hex".to_string(),
+            DecodeError::InvalidFrequency(_) => "invalid frequency".to_string(),
+            _ => "invalid record".to_string(),
+        };
+        let label = LabeledSpan::new_with_span(Some(label), span.clone());
+        Some(Box::new(std::iter::once(label)))
+    }
+}
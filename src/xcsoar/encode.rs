@@ -1,5 +1,7 @@
+use super::decode::decode_record;
 use super::fields::*;
-use crate::{File, Record};
+use super::{FREQUENCY_KEY, LINE_ENDING_KEY, LINE_KEY, VERSION_KEY};
+use crate::{EncodeReport, Field, File, Record};
 use encoding_rs::mem::{encode_latin1_lossy, is_str_latin1};
 use std::io::{Cursor, Write};
 use thiserror::Error;
@@ -23,29 +25,65 @@ pub enum EncodeError {
 ///     version: 123,
 ///     records: vec![
 ///         Record {
-///             flarm_id: "3EE3C7".to_string(),
+///             flarm_id: "3EE3C7".parse().unwrap(),
 ///             pilot_name: "Tobias Bieniek".to_string(),
 ///             airfield: "EDKA".to_string(),
 ///             plane_type: "LS6a".to_string(),
 ///             registration: "D-0816".to_string(),
 ///             call_sign: "SG".to_string(),
-///             frequency: "130.530".to_string(),
+///             frequency: Some("130.530".parse().unwrap()),
+///             extensions: Default::default(),
 ///         }
-///     ]
+///     ],
+///     extensions: Default::default(),
 /// };
 ///
 /// let result = flarmnet::xcsoar::encode_file(&file).unwrap();
 /// assert_eq!(result, br#"00007b
-/// 334545334337546f62696173204269656e69656b2020202020202045444b4120202020202020202020202020202020204c5336612020202020202020202020202020202020442d30383136205347203133302e353330
+/// 336565336337546f62696173204269656e69656b2020202020202045444b4120202020202020202020202020202020204c5336612020202020202020202020202020202020442d30383136205347203133302e353330
 /// "#);
 /// ```
 pub fn encode_file(file: &File) -> Result<Vec<u8>, EncodeError> {
+    let (buffer, _) = encode_file_with_report(file)?;
+    Ok(buffer)
+}
+
+/// Encodes a FlarmNet file and reports the fields that have been truncated
+/// to the fixed field lengths of the format.
+///
+/// # Examples
+///
+/// ```
+/// # use flarmnet::{Field, Record};
+/// let file = flarmnet::File {
+///     version: 123,
+///     records: vec![
+///         Record {
+///             flarm_id: "3EE3C7".parse().unwrap(),
+///             pilot_name: "Tobias Bieniek".to_string(),
+///             airfield: "EDKA".to_string(),
+///             plane_type: "LS6a".to_string(),
+///             registration: "D-0816".to_string(),
+///             call_sign: "SG16".to_string(),
+///             frequency: None,
+///             extensions: Default::default(),
+///         }
+///     ],
+///     extensions: Default::default(),
+/// };
+///
+/// let (_, report) = flarmnet::xcsoar::encode_file_with_report(&file).unwrap();
+/// assert_eq!(report.truncated.len(), 1);
+/// assert_eq!(report.truncated[0].field, Field::CallSign);
+/// assert_eq!(report.truncated[0].written, "SG1");
+/// ```
+pub fn encode_file_with_report(file: &File) -> Result<(Vec<u8>, EncodeReport), EncodeError> {
     let mut writer = Writer::new(Cursor::new(Vec::new()));
-    writer.write(file)?;
+    let report = writer.write_with_report(file)?;
 
     let buffer = writer.into_inner().into_inner();
 
-    Ok(buffer)
+    Ok((buffer, report))
 }
 
 #[derive(Clone)]
@@ -59,30 +97,67 @@ impl<W: Write> Writer<W> {
     }
 
     pub fn write(&mut self, file: &File) -> Result<(), EncodeError> {
-        self.write_version(file.version)?;
+        self.write_with_report(file)?;
+        Ok(())
+    }
+
+    /// Writes the file and reports the fields that have been truncated.
+    pub fn write_with_report(&mut self, file: &File) -> Result<EncodeReport, EncodeError> {
+        let mut report = EncodeReport::default();
+        let line_ending = line_ending(file);
+
+        self.writer.write_all(encode_version(file).as_bytes())?;
+        self.writer.write_all(line_ending.as_bytes())?;
+
         for record in &file.records {
-            self.write_record(record)?;
+            match original_line(record) {
+                Some(line) => self.writer.write_all(line.as_bytes())?,
+                None => self.write_record(record, &mut report)?,
+            }
+            self.writer.write_all(line_ending.as_bytes())?;
         }
 
-        Ok(())
+        Ok(report)
     }
 
-    fn write_version(&mut self, version: u32) -> Result<(), EncodeError> {
-        self.writer.write_fmt(format_args!("{:06x?}", version))?;
-        self.writer.write_all(b"\n")?;
+    /// Writes the record fields without line ending.
+    fn write_record(
+        &mut self,
+        record: &Record,
+        report: &mut EncodeReport,
+    ) -> Result<(), EncodeError> {
+        let flarm_id = format!("{:06x}", record.flarm_id);
+        self.write_str(&flarm_id, FLARM_ID_LENGTH)?;
+        self.write_field(record, Field::PilotName, PILOT_NAME_LENGTH, report)?;
+        self.write_field(record, Field::Airfield, AIRFIELD_LENGTH, report)?;
+        self.write_field(record, Field::PlaneType, PLANE_TYPE_LENGTH, report)?;
+        self.write_field(record, Field::Registration, REGISTRATION_LENGTH, report)?;
+        self.write_field(record, Field::CallSign, CALL_SIGN_LENGTH, report)?;
+        match record.extensions.get(FREQUENCY_KEY) {
+            Some(frequency) if record.frequency.is_none() => {
+                self.write_str(frequency, FREQUENCY_LENGTH)?;
+            }
+            _ => self.write_field(record, Field::Frequency, FREQUENCY_LENGTH, report)?,
+        }
 
         Ok(())
     }
 
-    fn write_record(&mut self, record: &Record) -> Result<(), EncodeError> {
-        self.write_str(&record.flarm_id, FLARM_ID_LENGTH)?;
-        self.write_str(&record.pilot_name, PILOT_NAME_LENGTH)?;
-        self.write_str(&record.airfield, AIRFIELD_LENGTH)?;
-        self.write_str(&record.plane_type, PLANE_TYPE_LENGTH)?;
-        self.write_str(&record.registration, REGISTRATION_LENGTH)?;
-        self.write_str(&record.call_sign, CALL_SIGN_LENGTH)?;
-        self.write_str(&record.frequency, FREQUENCY_LENGTH)?;
-        self.writer.write_all(b"\n")?;
+    fn write_field(
+        &mut self,
+        record: &Record,
+        field: Field,
+        length: usize,
+        report: &mut EncodeReport,
+    ) -> Result<(), EncodeError> {
+        let value = field.get(record);
+        self.write_str(&value, length)?;
+
+        // latin1 uses one byte per character
+        if value.chars().count() > length {
+            let written: String = value.chars().take(length).collect();
+            report.truncate(record, field, &written);
+        }
 
         Ok(())
     }
@@ -111,9 +186,62 @@ impl<W: Write> Writer<W> {
     }
 }
 
+/// Returns the line ending of the file, see [module documentation](super).
+pub(super) fn line_ending(file: &File) -> &'static str {
+    match file.extensions.get(LINE_ENDING_KEY) {
+        Some(value) if value == "\r\n" => "\r\n",
+        _ => "\n",
+    }
+}
+
+/// Encodes the version line without line ending.
+pub(super) fn encode_version(file: &File) -> String {
+    match file.extensions.get(VERSION_KEY) {
+        Some(line) if u32::from_str_radix(line, 16) == Ok(file.version) => line.clone(),
+        _ => format!("{:06x?}", file.version),
+    }
+}
+
+/// Encodes the fields of a single record, ignoring the original line.
+pub(crate) fn encode_record(
+    record: &Record,
+    report: &mut EncodeReport,
+) -> Result<String, EncodeError> {
+    let mut writer = Writer::new(Vec::with_capacity(LINE_LENGTH));
+    writer.write_record(record, report)?;
+
+    // the writer only produces hex digits
+    Ok(String::from_utf8(writer.into_inner()).unwrap())
+}
+
+/// Returns the line the record has been decoded from, if it has not been
+/// modified since.
+pub(super) fn original_line(record: &Record) -> Option<&str> {
+    let line = record.extensions.get(LINE_KEY)?;
+    is_original_line(line, record).then_some(line.as_str())
+}
+
+/// Checks whether the record was decoded from `line` and has not been
+/// modified since.
+fn is_original_line(line: &str, record: &Record) -> bool {
+    let Ok(decoded) = decode_record(line) else {
+        return false;
+    };
+
+    decoded.flarm_id == record.flarm_id
+        && decoded.pilot_name == record.pilot_name
+        && decoded.airfield == record.airfield
+        && decoded.plane_type == record.plane_type
+        && decoded.registration == record.registration
+        && decoded.call_sign == record.call_sign
+        && decoded.frequency == record.frequency
+}
+
 #[cfg(test)]
 mod tests {
-    use super::{EncodeError, Writer};
+    use super::{EncodeError, Writer, encode_file, encode_file_with_report};
+    use crate::xcsoar::decode_file;
+    use crate::{File, Record};
     use insta::assert_debug_snapshot;
     use std::io::Cursor;
 
@@ -141,4 +269,93 @@ mod tests {
             )
             "###);
     }
+
+    #[test]
+    fn encoding_round_trips_non_canonical_files() {
+        let input = "006FB0\r\n303030303046202020202020202020202020202020202020202020204D5950524F474F202020202020202020202020202020202020202020202020202020202020202020202020202020202020202020202020202020\r\n";
+        let decoded = decode_file(input).unwrap();
+        let file = File {
+            version: decoded.version,
+            records: decoded.records.into_iter().map(Result::unwrap).collect(),
+            extensions: decoded.extensions,
+        };
+        assert_eq!(encode_file(&file).unwrap(), input.as_bytes());
+    }
+
+    #[test]
+    fn encoding_ignores_original_line_of_modified_records() {
+        let file = "006fb0\n303030303046202020202020202020202020202020202020202020204D5950524F474F202020202020202020202020202020202020202020202020202020202020202020202020202020202020202020202020202020\n";
+        let decoded = decode_file(file).unwrap();
+        let mut record = decoded.records.into_iter().next().unwrap().unwrap();
+        record.airfield = "EDKA".to_string();
+        let file = File {
+            version: decoded.version,
+            records: vec![record],
+            extensions: decoded.extensions,
+        };
+        assert_eq!(
+            String::from_utf8(encode_file(&file).unwrap()).unwrap(),
+            "006fb0\n30303030306620202020202020202020202020202020202020202045444b4120202020202020202020202020202020202020202020202020202020202020202020202020202020202020202020202020202020202020\n"
+        );
+    }
+
+    #[test]
+    fn encoding_writes_back_invalid_frequencies() {
+        let file = "006fb0\n3030303030304dfc6c6c6572202020202020202020202020202020442d3231383820202020202020202020202020202041534b2d3133202020202020202020202020202020442d32313838202020203132392e393730\n";
+        let decoded = decode_file(file).unwrap();
+        let mut record = decoded.records.into_iter().next().unwrap().unwrap();
+        assert_eq!(record.frequency, None);
+        record.airfield = "EDKA".to_string();
+        let file = File {
+            version: decoded.version,
+            records: vec![record],
+            extensions: decoded.extensions,
+        };
+        assert_eq!(
+            String::from_utf8(encode_file(&file).unwrap()).unwrap(),
+            "006fb0\n3030303030304dfc6c6c657220202020202020202020202020202045444b41202020202020202020202020202020202041534b2d3133202020202020202020202020202020442d32313838202020203132392e393730\n"
+        );
+    }
+
+    #[test]
+    fn encoding_reports_truncated_fields() {
+        let file = File {
+            version: 1,
+            records: vec![Record {
+                flarm_id: "DD1234".parse().unwrap(),
+                pilot_name: "Hubertus Christophorus von Müller".to_string(),
+                airfield: String::new(),
+                plane_type: "Ka 6E".to_string(),
+                registration: "D-KABCD".to_string(),
+                call_sign: "ABCD".to_string(),
+                frequency: None,
+                extensions: Default::default(),
+            }],
+            extensions: Default::default(),
+        };
+
+        let (_, report) = encode_file_with_report(&file).unwrap();
+        assert_debug_snapshot!(report, @r###"
+        EncodeReport {
+            truncated: [
+                TruncatedField {
+                    flarm_id: FlarmId(
+                        DD1234,
+                    ),
+                    field: PilotName,
+                    original: "Hubertus Christophorus von Müller",
+                    written: "Hubertus Christophoru",
+                },
+                TruncatedField {
+                    flarm_id: FlarmId(
+                        DD1234,
+                    ),
+                    field: CallSign,
+                    original: "ABCD",
+                    written: "ABC",
+                },
+            ],
+        }
+        "###);
+    }
 }
@@ -1,11 +1,76 @@
 //! Decoder/Encoder for LXNavigation/XCSoar/WinPilot/LK8000/ClearNav file format.
 //!
-//! The [decode_file] function can be used to decode FlarmNet files. The
-//! [encode_file] function can be used to write such files.
+//! The [decode_file] and [decode_reader] functions can be used to decode
+//! FlarmNet files, while the [Reader] decodes the records one at a time. The [encode_file] function can
+//! be used to write such files. Since the format has fixed field lengths,
+//! [encode_file_with_report] can be used to find out which fields have been
+//! truncated.
+//!
+//! ## Extensions
+//!
+//! Decoding and encoding an unmodified file results in the same bytes again.
+//! For that, the decoder stores the following [Extensions](crate::Extensions)
+//! if the content differs from what the encoder would write by default:
+//!
+//! - `xcsoar.version` (file): the original version line, e.g. if it uses
+//!   uppercase hex digits
+//! - `xcsoar.line_ending` (file): `\r\n` if the file uses Windows line endings
+//! - `xcsoar.line` (record): the original line, e.g. if it uses uppercase hex
+//!   digits or a non-canonical frequency. The encoder only uses it if the
+//!   record has not been modified in the meantime.
+//! - `xcsoar.frequency` (record): the original frequency if it is not a valid
+//!   [Frequency](crate::Frequency), see
+//!   [accept_invalid_frequencies](crate::DecodeOptions::accept_invalid_frequencies).
+//!   The encoder writes it if the record has no frequency.
 
+#[cfg(feature = "async")]
+mod async_io;
 mod decode;
+#[cfg(feature = "miette")]
+mod diagnostic;
 mod encode;
-mod fields;
+pub(crate) mod fields;
 
+#[cfg(feature = "async")]
+pub use async_io::*;
 pub use decode::*;
 pub use encode::*;
+
+const FREQUENCY_KEY: &str = "xcsoar.frequency";
+const LINE_KEY: &str = "xcsoar.line";
+const LINE_ENDING_KEY: &str = "xcsoar.line_ending";
+const VERSION_KEY: &str = "xcsoar.version";
+
+/// [Codec](crate::Codec) implementation for the XCSoar file format.
+#[derive(Debug, Clone, Copy)]
+pub struct XCSoar;
+
+impl crate::Codec for XCSoar {
+    const FORMAT: crate::Format = crate::Format::XCSoar;
+
+    fn decode_with_options(
+        data: &[u8],
+        options: &crate::DecodeOptions,
+    ) -> Result<crate::DecodedFile, crate::Error> {
+        // the format only consists of ASCII characters, so any invalid
+        // UTF-8 will be reported as unexpected characters per record
+        let content = String::from_utf8_lossy(data);
+        let decoded = decode_file_with_options(&content, options)?;
+        Ok(crate::DecodedFile::new(
+            Self::FORMAT,
+            decoded.version,
+            decoded.records,
+            decoded.extensions,
+        ))
+    }
+
+    fn encode(file: &crate::File) -> Result<Vec<u8>, crate::Error> {
+        Ok(encode_file(file)?)
+    }
+
+    fn encode_with_report(
+        file: &crate::File,
+    ) -> Result<(Vec<u8>, crate::EncodeReport), crate::Error> {
+        Ok(encode_file_with_report(file)?)
+    }
+}
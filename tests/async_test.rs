@@ -0,0 +1,102 @@
+#![cfg(feature = "async")]
+
+use flarmnet::File;
+
+async fn read_fixture(name: &str) -> tokio::fs::File {
+    tokio::fs::File::open(format!("tests/fixtures/{name}"))
+        .await
+        .unwrap()
+}
+
+#[tokio::test]
+async fn lx_round_trips() {
+    let decoded = flarmnet::lx::decode_async_reader(read_fixture("lx.fln").await)
+        .await
+        .unwrap();
+    let file = File {
+        version: decoded.version,
+        records: decoded.records.into_iter().map(Result::unwrap).collect(),
+        extensions: decoded.extensions,
+    };
+
+    let mut writer = flarmnet::lx::AsyncWriter::new(Vec::new());
+    writer.write(&file).await.unwrap();
+    assert_eq!(writer.into_inner(), include_bytes!("fixtures/lx.fln"));
+}
+
+#[tokio::test]
+async fn tdb_round_trips() {
+    let decoded = flarmnet::tdb::decode_async_reader(read_fixture("flarmnet.tdb").await)
+        .await
+        .unwrap();
+    let file = File {
+        version: decoded.version,
+        records: decoded.records.into_iter().map(Result::unwrap).collect(),
+        extensions: decoded.extensions,
+    };
+
+    let mut writer = flarmnet::tdb::AsyncWriter::new(Vec::new());
+    writer.write(&file).await.unwrap();
+    assert_eq!(writer.into_inner(), include_bytes!("fixtures/flarmnet.tdb"));
+}
+
+#[tokio::test]
+async fn xcsoar_round_trips() {
+    let decoded = flarmnet::xcsoar::decode_async_reader(read_fixture("data.fln").await)
+        .await
+        .unwrap();
+    let file = File {
+        version: decoded.version,
+        records: decoded.records.into_iter().map(Result::unwrap).collect(),
+        extensions: decoded.extensions,
+    };
+
+    let mut writer = flarmnet::xcsoar::AsyncWriter::new(Vec::new());
+    writer.write(&file).await.unwrap();
+    assert_eq!(writer.into_inner(), include_bytes!("fixtures/data.fln"));
+}
+
+#[tokio::test]
+async fn lx_reader_matches_sync_reader() {
+    let mut reader = flarmnet::lx::AsyncReader::new(read_fixture("lx.fln").await)
+        .await
+        .unwrap();
+    let expected = flarmnet::lx::Reader::new(&include_bytes!("fixtures/lx.fln")[..]).unwrap();
+    assert_eq!(reader.version(), expected.version());
+
+    for expected in expected {
+        let record = reader.next().await.unwrap().unwrap();
+        assert_eq!(record, expected.unwrap());
+    }
+    assert!(reader.next().await.is_none());
+}
+
+#[tokio::test]
+async fn tdb_reader_matches_sync_reader() {
+    let mut reader = flarmnet::tdb::AsyncReader::new(read_fixture("flarmnet.tdb").await)
+        .await
+        .unwrap();
+    let expected =
+        flarmnet::tdb::Reader::new(&include_bytes!("fixtures/flarmnet.tdb")[..]).unwrap();
+    assert_eq!(reader.version(), expected.version());
+
+    for expected in expected {
+        let record = reader.next().await.unwrap().unwrap();
+        assert_eq!(record, expected.unwrap());
+    }
+    assert!(reader.next().await.is_none());
+}
+
+#[tokio::test]
+async fn xcsoar_reader_matches_sync_reader() {
+    let file = tokio::io::BufReader::new(read_fixture("data.fln").await);
+    let mut reader = flarmnet::xcsoar::AsyncReader::new(file).await.unwrap();
+    let expected = flarmnet::xcsoar::Reader::new(&include_bytes!("fixtures/data.fln")[..]).unwrap();
+    assert_eq!(reader.version(), expected.version());
+
+    for expected in expected {
+        let record = reader.next().await.unwrap().unwrap();
+        assert_eq!(record, expected.unwrap());
+    }
+    assert!(reader.next().await.is_none());
+}
@@ -0,0 +1,128 @@
+#![cfg(feature = "cli")]
+
+use flarmnet::{File, Format, Record};
+use std::process::Command;
+
+#[test]
+fn convert_reports_truncated_fields() {
+    let file = File {
+        version: 123,
+        records: vec![Record {
+            flarm_id: "3EE3C7".parse().unwrap(),
+            pilot_name: "Tobias Bieniek".to_string(),
+            airfield: "EDKA".to_string(),
+            plane_type: "LS6a".to_string(),
+            registration: "D-0816".to_string(),
+            call_sign: "SG16".to_string(),
+            frequency: None,
+            extensions: Default::default(),
+        }],
+        extensions: Default::default(),
+    };
+
+    let dir = std::env::temp_dir().join(format!("flarmnet-cli-test-{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    let input = dir.join("input.tdb");
+    let output = dir.join("output.fln");
+    std::fs::write(&input, Format::Tdb.encode(&file).unwrap()).unwrap();
+
+    let result = Command::new(env!("CARGO_BIN_EXE_flarmnet"))
+        .args(["convert", "--to", "xcsoar"])
+        .args([&input, &output])
+        .output()
+        .unwrap();
+
+    assert!(result.status.success());
+    let stderr = String::from_utf8(result.stderr).unwrap();
+    assert_eq!(
+        stderr,
+        "Read tdb file, version 123, with 1 records\n\
+         Truncated call_sign of record 3EE3C7: \"SG16\" -> \"SG1\"\n\
+         Wrote xcsoar file with 1 records\n"
+    );
+
+    let converted = std::fs::read(&output).unwrap();
+    let converted = flarmnet::decode_any(&converted).unwrap();
+    assert_eq!(converted.format, Format::XCSoar);
+    assert_eq!(converted.version, 123);
+
+    std::fs::remove_dir_all(dir).unwrap();
+}
+
+#[test]
+fn convert_reports_location_of_invalid_records() {
+    let input = "006fb0\n\
+        3030303030304dfc6c6c6572202020202020202020202020202020442d3231383820202020202020202020202020202041534b2d3133202020202020202020202020202020442d32313838202020203132332e313530\n\
+        3030303030314dfc6c6c6572\n";
+
+    let dir = std::env::temp_dir().join(format!("flarmnet-cli-test-{}-2", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    let output = dir.join("output.tdb");
+    let input_path = dir.join("input.fln");
+    std::fs::write(&input_path, input).unwrap();
+
+    let result = Command::new(env!("CARGO_BIN_EXE_flarmnet"))
+        .args(["convert", "--to", "tdb"])
+        .args([&input_path, &output])
+        .output()
+        .unwrap();
+
+    assert!(result.status.success());
+    let stderr = String::from_utf8(result.stderr).unwrap();
+    assert_eq!(
+        stderr,
+        "Read xcsoar file, version 28592, with 2 records\n\
+         Skipping invalid record #2: invalid record in line 3: unexpected line length: 24 characters\n\
+         Wrote tdb file with 1 records\n"
+    );
+
+    std::fs::remove_dir_all(dir).unwrap();
+}
+
+#[test]
+fn convert_reads_csv_files() {
+    let input = "flarm_id,registration,call_sign,plane_type\n\
+        3EE3C7,D-0816,SG,LS6a\n\
+        XYZ,D-KABC,,\n";
+
+    let dir = std::env::temp_dir().join(format!("flarmnet-cli-test-{}-3", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    let input_path = dir.join("clubs.csv");
+    let output = dir.join("output.fln");
+    std::fs::write(&input_path, input).unwrap();
+
+    let result = Command::new(env!("CARGO_BIN_EXE_flarmnet"))
+        .args(["convert", "--to", "xcsoar"])
+        .args([&input_path, &output])
+        .output()
+        .unwrap();
+
+    assert!(!result.status.success());
+    let stderr = String::from_utf8(result.stderr).unwrap();
+    assert!(stderr.starts_with("Error: unknown input file format, use --from to specify it\n"));
+
+    let result = Command::new(env!("CARGO_BIN_EXE_flarmnet"))
+        .args(["convert", "--from", "csv", "--to", "xcsoar"])
+        .args([&input_path, &output])
+        .output()
+        .unwrap();
+
+    assert!(result.status.success());
+    let stderr = String::from_utf8(result.stderr).unwrap();
+    assert_eq!(
+        stderr,
+        "Read csv file, version 0, with 2 records\n\
+         Skipping invalid record #2: invalid FLARM id: XYZ\n\
+         Wrote xcsoar file with 1 records\n"
+    );
+
+    let converted = std::fs::read(&output).unwrap();
+    let converted = flarmnet::decode_any(&converted).unwrap();
+    assert_eq!(converted.format, Format::XCSoar);
+    assert_eq!(
+        converted.records[0].as_ref().unwrap().registration,
+        "D-0816"
+    );
+
+    std::fs::remove_dir_all(dir).unwrap();
+}
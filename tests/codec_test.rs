@@ -0,0 +1,14 @@
+use flarmnet::Format;
+
+#[test]
+fn it_works() {
+    let fixture = include_bytes!("fixtures/lx.fln");
+    let file = Format::Lx.decode(fixture).unwrap().into_file();
+
+    for format in Format::ALL {
+        let encoded = format.encode(&file).unwrap();
+        let decoded = format.decode(&encoded).unwrap();
+        assert_eq!(decoded.format, *format);
+        assert_eq!(decoded.into_file(), file, "{format:?} round trip failed");
+    }
+}
@@ -0,0 +1,25 @@
+#![cfg(feature = "csv")]
+
+use flarmnet::{decode_any, diff};
+
+#[test]
+fn csv_round_trips_other_formats() {
+    let original = decode_any(include_bytes!("fixtures/data.fln"))
+        .unwrap()
+        .into_file();
+
+    let encoded = flarmnet::csv::encode_file(&original).unwrap();
+    let decoded = flarmnet::csv::decode_file(&encoded).unwrap();
+    let records = decoded
+        .records
+        .into_iter()
+        .collect::<Result<Vec<_>, _>>()
+        .unwrap();
+
+    let file = flarmnet::File {
+        version: original.version,
+        records,
+        extensions: Default::default(),
+    };
+    assert!(diff(&original, &file).is_empty());
+}
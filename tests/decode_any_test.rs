@@ -0,0 +1,19 @@
+use flarmnet::{Format, decode_any};
+
+fn decode(fixture: &[u8]) -> (Format, u32, usize) {
+    let decoded = decode_any(fixture).unwrap();
+    let ok_count = decoded.records.iter().filter(|it| it.is_ok()).count();
+    (decoded.format, decoded.version, ok_count)
+}
+
+#[test]
+fn it_works() {
+    let xcsoar = include_bytes!("fixtures/data.fln");
+    assert_eq!(decode(xcsoar), (Format::XCSoar, 28592, 3));
+
+    let lx = include_bytes!("fixtures/lx.fln");
+    assert_eq!(decode(lx), (Format::Lx, 28592, 3));
+
+    let tdb = include_bytes!("fixtures/flarmnet.tdb");
+    assert_eq!(decode(tdb), (Format::Tdb, 28592, 3));
+}
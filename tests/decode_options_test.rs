@@ -0,0 +1,16 @@
+use flarmnet::{DecodeOptions, decode_any_with_options};
+
+#[test]
+fn fixtures_pass_strict_validation() {
+    let fixtures: [&[u8]; 3] = [
+        include_bytes!("fixtures/data.fln"),
+        include_bytes!("fixtures/lx.fln"),
+        include_bytes!("fixtures/flarmnet.tdb"),
+    ];
+
+    for fixture in fixtures {
+        let result = decode_any_with_options(fixture, &DecodeOptions::STRICT).unwrap();
+        assert_eq!(result.records.len(), 3);
+        assert!(result.records.iter().all(Result::is_ok));
+    }
+}
@@ -0,0 +1,28 @@
+use flarmnet::{DiffOptions, decode_any, diff, diff_with_options};
+
+#[test]
+fn diff_works_across_formats() {
+    let old = decode_any(include_bytes!("fixtures/data.fln")).unwrap();
+    let new = decode_any(include_bytes!("fixtures/flarmnet.tdb")).unwrap();
+    let (mut old, mut new) = (old.into_file(), new.into_file());
+
+    assert!(diff(&old, &new).is_empty());
+
+    // TDB supports longer call signs than XCSoar
+    new.records[2].call_sign = "X27 Foo".to_string();
+    assert_eq!(diff(&old, &new).changed.len(), 1);
+
+    let options = DiffOptions {
+        ignore_truncation: true,
+    };
+    assert!(diff_with_options(&old, &new, &options).is_empty());
+
+    // TDB truncates text fields to 15 bytes
+    new.records[0].pilot_name = "Hans-Dieter Mü".to_string();
+    old.records[0].pilot_name = "Hans-Dieter Müller".to_string();
+    assert!(diff_with_options(&old, &new, &options).is_empty());
+
+    // shorter prefixes are a change
+    new.records[0].pilot_name = "Hans-Dieter".to_string();
+    assert_eq!(diff_with_options(&old, &new, &options).changed.len(), 1);
+}
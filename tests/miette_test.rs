@@ -0,0 +1,56 @@
+#![cfg(all(feature = "miette", feature = "lx", feature = "xcsoar"))]
+
+use flarmnet::DecodeOptions;
+use insta::assert_snapshot;
+use miette::{NarratableReportHandler, Report};
+use std::io::Read;
+
+fn render(report: Report) -> String {
+    let mut output = String::new();
+    NarratableReportHandler::new()
+        .render_report(&mut output, report.as_ref())
+        .unwrap();
+    output
+}
+
+#[test]
+fn xcsoar_errors_point_at_invalid_fields() {
+    let file = "006fb0\n\
+        3030303030474dfc6c6c6572202020202020202020202020202020442d3231383820202020202020202020202020202041534b2d3133202020202020202020202020202020442d32313838202020203132332e313530\n\
+        303030303030nodc6c6c6572202020202020202020202020202020442d3231383820202020202020202020202020202041534b2d3133202020202020202020202020202020442d32313838202020203132332e313530\n";
+
+    let result = flarmnet::xcsoar::decode_file(file).unwrap();
+    let reports = result.records.into_iter().map(|result| {
+        let report = Report::new(result.unwrap_err()).with_source_code(file.to_string());
+        render(report)
+    });
+
+    assert_snapshot!(reports.collect::<Vec<_>>().join("\n---\n\n"));
+}
+
+#[test]
+fn lx_errors_point_at_invalid_elements() {
+    let xml = r#"<?xml version="1.0" encoding="UTF-8"?>
+<FLARMNET Version="006FB0">
+<FLARMDATA FlarmID="3EE3C7"><NAME>Tobias</NAME><FREQUENCY>108.000</FREQUENCY></FLARMDATA>
+</FLARMNET>
+"#;
+    let mut file = flarmnet::lx::cipher::Writer::new(Vec::new());
+    std::io::copy(&mut xml.as_bytes(), &mut file).unwrap();
+    let file = file.into_inner();
+
+    // the spans refer to the decrypted XML
+    let mut decrypted = String::new();
+    flarmnet::lx::cipher::Reader::new(&file[..])
+        .read_to_string(&mut decrypted)
+        .unwrap();
+    assert_eq!(decrypted, xml);
+
+    let options = DecodeOptions {
+        accept_invalid_frequencies: false,
+        ..Default::default()
+    };
+    let result = flarmnet::decode_any_with_options(&file, &options).unwrap();
+    let error = result.records.into_iter().next().unwrap().unwrap_err();
+    assert_snapshot!(render(Report::new(error).with_source_code(decrypted)));
+}
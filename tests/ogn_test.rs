@@ -0,0 +1,44 @@
+#![cfg(feature = "ogn")]
+
+use flarmnet::ogn::{Reader, Writer, decode_file, json};
+use insta::assert_debug_snapshot;
+
+#[test]
+fn decoding_works() {
+    let fixture = include_str!("fixtures/ogn-ddb.csv");
+    assert_debug_snapshot!(decode_file(fixture));
+}
+
+#[test]
+fn devices_round_trip() {
+    let fixture = include_str!("fixtures/ogn-ddb.csv");
+    let devices = Reader::new(fixture.as_bytes())
+        .unwrap()
+        .collect::<Result<Vec<_>, _>>()
+        .unwrap();
+
+    let mut writer = Writer::new(Vec::new());
+    writer.write_devices(&devices).unwrap();
+    assert_eq!(writer.into_inner(), fixture.as_bytes());
+}
+
+#[test]
+fn json_decoding_matches_csv() {
+    let csv = decode_file(include_str!("fixtures/ogn-ddb.csv")).unwrap();
+    let json = json::decode_file(include_str!("fixtures/ogn-ddb.json")).unwrap();
+    assert_eq!(format!("{:?}", json.records), format!("{:?}", csv.records));
+}
+
+#[test]
+fn json_devices_round_trip() {
+    let fixture = include_str!("fixtures/ogn-ddb.json");
+    let devices = json::decode_devices(fixture.as_bytes())
+        .unwrap()
+        .into_iter()
+        .collect::<Result<Vec<_>, _>>()
+        .unwrap();
+
+    let mut writer = json::Writer::new(Vec::new());
+    writer.write_devices(&devices).unwrap();
+    assert_eq!(writer.into_inner(), fixture.as_bytes());
+}
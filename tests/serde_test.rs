@@ -0,0 +1,75 @@
+#![cfg(feature = "serde")]
+
+use flarmnet::{File, decode_any};
+use insta::assert_snapshot;
+
+#[test]
+fn serialization_works() {
+    let file = decode_any(include_bytes!("fixtures/data.fln"))
+        .unwrap()
+        .into_file();
+
+    assert_snapshot!(serde_json::to_string_pretty(&file).unwrap());
+}
+
+#[test]
+fn deserialization_round_trips() {
+    let file = decode_any(include_bytes!("fixtures/flarmnet.tdb"))
+        .unwrap()
+        .into_file();
+
+    let json = serde_json::to_string(&file).unwrap();
+    assert_eq!(serde_json::from_str::<File>(&json).unwrap(), file);
+}
+
+#[test]
+fn deserialization_uses_defaults() {
+    let json = r#"{
+        "version": 1,
+        "records": [{
+            "flarm_id": "3ee3c7",
+            "pilot_name": "Tobias Bieniek",
+            "airfield": "EDKA",
+            "plane_type": "LS6a",
+            "registration": "D-0816",
+            "call_sign": "SG",
+            "frequency": "130.53"
+        }]
+    }"#;
+
+    let file: File = serde_json::from_str(json).unwrap();
+    assert!(file.extensions.is_empty());
+    assert_eq!(file.records[0].flarm_id.to_string(), "3EE3C7");
+    assert_eq!(file.records[0].frequency.unwrap().to_string(), "130.530");
+    assert!(file.records[0].extensions.is_empty());
+}
+
+#[test]
+#[cfg(feature = "ogn")]
+fn ogn_device_serialization_round_trips() {
+    use flarmnet::ogn::{Device, DeviceType};
+
+    let device = Device {
+        device_type: DeviceType::Icao,
+        device_id: "3EE3C7".parse().unwrap(),
+        aircraft_model: "LS-6".to_string(),
+        registration: "D-0816".to_string(),
+        cn: "SG".to_string(),
+        tracked: true,
+        identified: false,
+    };
+
+    let json = serde_json::to_string_pretty(&device).unwrap();
+    assert_snapshot!(json, @r###"
+    {
+      "device_type": "icao",
+      "device_id": "3EE3C7",
+      "aircraft_model": "LS-6",
+      "registration": "D-0816",
+      "cn": "SG",
+      "tracked": true,
+      "identified": false
+    }
+    "###);
+    assert_eq!(serde_json::from_str::<Device>(&json).unwrap(), device);
+}
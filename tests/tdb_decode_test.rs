@@ -6,3 +6,31 @@ fn it_works() {
     let fixture = include_bytes!("fixtures/flarmnet.tdb");
     assert_debug_snapshot!(decode_file(fixture));
 }
+
+#[test]
+fn reader_matches_decode_file() {
+    let fixture = include_bytes!("fixtures/flarmnet.tdb");
+    let decoded = decode_file(fixture).unwrap();
+
+    let reader = flarmnet::tdb::Reader::new(&fixture[..]).unwrap();
+    assert_eq!(reader.version(), decoded.version);
+    assert_eq!(reader.record_count(), decoded.records.len());
+
+    let records = reader.collect::<Result<Vec<_>, _>>().unwrap();
+    let expected = decoded
+        .records
+        .into_iter()
+        .collect::<Result<Vec<_>, _>>()
+        .unwrap();
+    assert_eq!(records, expected);
+}
+
+#[test]
+fn decode_reader_matches_decode_file() {
+    let fixture = include_bytes!("fixtures/flarmnet.tdb");
+    let file = std::fs::File::open("tests/fixtures/flarmnet.tdb").unwrap();
+    assert_eq!(
+        format!("{:?}", flarmnet::tdb::decode_reader(file)),
+        format!("{:?}", decode_file(fixture)),
+    );
+}
@@ -11,6 +11,7 @@ fn it_works() {
             .into_iter()
             .filter_map(|it| it.ok())
             .collect(),
+        extensions: decoded.extensions,
     };
     assert_eq!(encode_file(&file).unwrap(), fixture.as_ref());
 }
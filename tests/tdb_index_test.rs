@@ -0,0 +1,73 @@
+use flarmnet::DecodeOptions;
+use flarmnet::tdb::{DecodeError, Index, decode_file};
+
+#[test]
+fn index_finds_all_records() {
+    let fixture = include_bytes!("fixtures/flarmnet.tdb");
+    let decoded = decode_file(fixture).unwrap();
+
+    let index = Index::new(fixture).unwrap();
+    assert_eq!(index.version(), decoded.version);
+    assert_eq!(index.len(), decoded.records.len());
+
+    for record in decoded.records {
+        let record = record.unwrap();
+        assert_eq!(index.get(record.flarm_id).unwrap(), Some(record));
+    }
+
+    assert_eq!(index.get("ABCDEF".parse().unwrap()).unwrap(), None);
+}
+
+#[test]
+fn index_fails_for_truncated_file() {
+    let fixture = include_bytes!("fixtures/flarmnet.tdb");
+    let truncated = &fixture[..fixture.len() - 1];
+    assert!(matches!(
+        Index::new(truncated),
+        Err(DecodeError::UnexpectedEof)
+    ));
+
+    assert!(matches!(
+        Index::new(b"foo"),
+        Err(DecodeError::UnexpectedEof)
+    ));
+
+    let mut fixture = fixture.to_vec();
+    // set the record count to the maximum
+    fixture[8..12].copy_from_slice(&u32::MAX.to_le_bytes());
+    assert!(matches!(
+        Index::new(&fixture),
+        Err(DecodeError::UnexpectedEof)
+    ));
+}
+
+#[test]
+fn index_validates_index_entries() {
+    let mut fixture = include_bytes!("fixtures/flarmnet.tdb").to_vec();
+    // swap the first two index entries
+    let (first, second) = fixture[12..20].split_at_mut(4);
+    first.swap_with_slice(second);
+    assert!(matches!(
+        Index::new(&fixture),
+        Err(DecodeError::UnsortedIndex)
+    ));
+
+    let mut fixture = include_bytes!("fixtures/flarmnet.tdb").to_vec();
+    // set the index entry of the last record to FFFFFF
+    fixture[20..24].copy_from_slice(&0xFFFFFFu32.to_le_bytes());
+    let index = Index::new(&fixture).unwrap();
+    let result = index.get("FFFFFF".parse().unwrap());
+    assert!(matches!(result, Err(DecodeError::IndexMismatch { .. })));
+}
+
+#[test]
+fn index_validates_padding() {
+    let mut fixture = include_bytes!("fixtures/flarmnet.tdb").to_vec();
+    // set the first padding byte after the three index entries
+    fixture[24] = 0x42;
+    assert!(Index::with_options(&fixture, &DecodeOptions::LENIENT).is_ok());
+    assert!(matches!(
+        Index::new(&fixture),
+        Err(DecodeError::InvalidPadding(_))
+    ));
+}